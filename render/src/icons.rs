@@ -1,5 +1,4 @@
 use std::{
-    dbg,
     fs::{self, File},
     path::PathBuf,
 };
@@ -7,8 +6,6 @@ use std::{
 use cairo::ImageSurface;
 use texture_synthesis::{Dims, ImageSource};
 
-use crate::make_imgs;
-
 use super::util;
 
 pub(super) fn make_alphabet(
@@ -18,25 +15,25 @@ pub(super) fn make_alphabet(
     width: i32,
     font_path: &str,
     target_dir: &str,
-    background_color: make_imgs::Myrgb,
-    text_color: make_imgs::Myrgb,
+    background_color: crate::Myrgb,
+    text_color: crate::Myrgb,
 ) {
     let font = util::path_to_font(font_path);
     let _ = fs::create_dir_all(target_dir.clone());
     let base_fontsize = height as f64;
     let mut fontsize = height as f64;
     let spacing = height as f64 / 40.0;
-    dbg!("making_alp", target_dir);
+    let _span = tracing::info_span!("make_alphabet", target_dir).entered();
     for i in min..max + 1 {
         fontsize = base_fontsize;
         let s = char::from_u32(i as u32).unwrap().to_string();
         let (surface, mut context) = util::make_surface(height, width);
         context.set_font_face(&font);
         context.set_line_width(spacing);
-        context = make_imgs::set_color(context, background_color);
+        context = crate::set_color(context, background_color);
         context.rectangle(0., 0., f64::from(width), f64::from(height));
         let _ = context.fill();
-        context = make_imgs::set_color(context, text_color);
+        context = crate::set_color(context, text_color);
         context.set_font_size(fontsize as f64);
         let mut tx = context.text_extents(&s).unwrap();
         while tx.x_advance > 500.0 || tx.y_advance > 500.0 || tx.height > 500.0 || tx.width > 500.0
@@ -44,7 +41,6 @@ pub(super) fn make_alphabet(
             fontsize = fontsize * 0.9;
             context.set_font_size(fontsize as f64);
             tx = context.text_extents(&s).unwrap();
-            //dbg!(tx);
         }
         fontsize = fontsize * 0.9;
         context.set_font_size(fontsize as f64);
@@ -58,13 +54,12 @@ pub(super) fn make_alphabet(
             .expect("Couldn't create 'file.png'");
         surface.write_to_png(&mut file).unwrap()
     }
-    dbg!("done_alp", target_dir);
 }
 
 pub(super) fn setup_alphabets(
     font_img_dir: &str,
-    background_color: make_imgs::Myrgb,
-    text_color: make_imgs::Myrgb,
+    background_color: crate::Myrgb,
+    text_color: crate::Myrgb,
 ) {
     let real_glyth_dir = "".to_string() + font_img_dir + &"real/".to_string();
     if let Ok(_x) = fs::read_dir(real_glyth_dir.clone()) {
@@ -120,7 +115,7 @@ pub(super) fn get_synt_glyth_path(glyth_id: i32, font_img_dir: &str) -> Option<P
     let _ = fs::create_dir_all(synth_glyth_dir.clone());
     let path = synth_glyth_dir + &format!("{glyth_id}.png");
     if let Ok(_) = fs::read(&path) {
-        print!("y");
+        tracing::trace!(path = %path, "synthesized glyph already made");
         return Some(PathBuf::from(path));
     } else {
         return None;
@@ -148,7 +143,7 @@ pub(super) fn get_synth_glyth(glyth_id: i32, font_img_dir: &str) -> PathBuf {
         .map(|i| i.unwrap().path())
         .collect();
 
-    let now = std::time::Instant::now();
+    let _span = tracing::info_span!("synthesize_glyph", glyth_id).entered();
     let mut sb = texture_synthesis::Session::builder();
     sb = sb
         .seed(glyth_id as u64)
@@ -163,7 +158,7 @@ pub(super) fn get_synth_glyth(glyth_id: i32, font_img_dir: &str) -> PathBuf {
         if i.exists() && i.is_file() {
             sb = sb.add_example(texture_synthesis::Example::builder(i).with_guide(&mask));
         } else {
-            println!("{:?}", i);
+            tracing::warn!("glyph example {:?} is missing", i);
         }
     }
     let run = sb.load_target_guide(&mask).build().unwrap();
@@ -171,7 +166,5 @@ pub(super) fn get_synth_glyth(glyth_id: i32, font_img_dir: &str) -> PathBuf {
     let done_img = done.into_image();
 
     done_img.save(path.clone()).unwrap();
-    let timing = now.elapsed().as_secs();
-    println!("Done: {glyth_id} Timing: {timing}");
     PathBuf::from(path)
 }
@@ -12,18 +12,19 @@ use palette::{FromColor, Hsl, Srgb};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 
-use crate::celldata::{CellState, CellStateData, CellStateVariant};
-use crate::resource::Resource;
-use crate::{actionmachine, visualize_cell};
-use crate::{
-    celldata::{self},
-    resource,
-};
+use sim::celldata::{CellState, CellStateData, CellStateVariant};
+use sim::resource::Resource;
+use sim::{actionmachine, celldata, resource};
 
 mod icons;
+pub mod map;
 mod util;
 
-type Myrgb = palette::rgb::Rgb<Srgb, f64>;
+pub type Myrgb = palette::rgb::Rgb<Srgb, f64>;
+
+// size of a cell in the UI at the starting zoom level, images are drawn at four times that
+pub const START_CELL_X_SIZE: f32 = 100.0;
+pub const START_CELL_Y_SIZE: f32 = 125.0;
 
 const BASE: &str = "./img/";
 
@@ -50,6 +51,43 @@ fn get_glyth_map() -> HashMap<String, i32> {
     util::get_with_file_cache(&dir, "/glyth_map.json", |i| i, HashMap::new)
 }
 
+// new ids are written back, so a resource keeps its glyth in the HUD and on the tiles
+fn save_glyth_map(m: &HashMap<String, i32>) {
+    let path = "".to_string() + BASE + "/glyth_map.json";
+    fs::write(path, serde_json::to_string(m).unwrap()).unwrap();
+}
+
+fn glyth_path(name: String, id: i32, glyth_dir: &str) -> PathBuf {
+    if let Some(path) = icons::get_synt_glyth_path(id, glyth_dir) {
+        path
+    } else {
+        icons::get_real_glyth_path(name, id, glyth_dir)
+    }
+}
+
+// the glyths hub tiles use for each resource, slow the first time as the alphabet is rendered
+pub fn resource_icons() -> HashMap<resource::ResourceType, PathBuf> {
+    let cv = CellStateVariant::Hub;
+    let name = cv.to_string();
+    let (background_color, front_color) = get_color_pair(celldata::unit_state(cv));
+    let glyth_dir = "".to_string() + BASE + &name + "/glyths/";
+    icons::setup_alphabets(&glyth_dir, background_color, front_color);
+    let mut glyth_ids = get_glyth_map();
+    let mut next_id = glyth_ids.values().max().map_or(0, |max| *max + 1);
+    let ret = enum_iterator::all::<resource::ResourceType>()
+        .map(|t| {
+            let key = format!("{:?}", t);
+            let id = *glyth_ids.entry(key).or_insert_with(|| {
+                next_id = next_id + 1;
+                next_id - 1
+            });
+            (t, glyth_path(name.clone(), id, &glyth_dir))
+        })
+        .collect();
+    save_glyth_map(&glyth_ids);
+    ret
+}
+
 fn new_color_pair() -> ColorSource {
     let mut rng = rand::thread_rng();
     ColorSource {
@@ -84,11 +122,12 @@ fn cellstate_to_img_src_data(c: CellState) -> ImageSourceData {
     ImageSourceData { name, data }
 }
 
-pub(crate) fn make_image(c: CellState) -> PathBuf {
+pub fn make_image(c: CellState) -> PathBuf {
+    let _span = tracing::debug_span!("make_image", variant = %c.variant).entered();
     let sd = cellstate_to_img_src_data(c);
     let path = make_path(sd.clone());
     if let Ok(_) = fs::read(&path) {
-        print!("x");
+        tracing::trace!(?path, "already made");
         return path;
     }
     let (background_color, front_color) = get_color_pair(c);
@@ -114,17 +153,9 @@ pub(crate) fn make_image(c: CellState) -> PathBuf {
                 ret
             }
         })
-        .map(|(name, id)| {
-            if let Some(path) = icons::get_synt_glyth_path(id, &glyth_dir) {
-                (name, path)
-            } else {
-                (
-                    name,
-                    icons::get_real_glyth_path(sd.name.clone(), id, &glyth_dir),
-                )
-            }
-        })
+        .map(|(name, id)| (name, glyth_path(sd.name.clone(), id, &glyth_dir)))
         .collect();
+    save_glyth_map(&glyth_ids);
     //let icon = get_id(glyth_map, icon_string);
     //let img_path = icons::get_synth_glyth(icon, glyth_dir);
 
@@ -139,8 +170,8 @@ fn make_image_int(
     (background_color, front_color): (Myrgb, Myrgb),
 ) {
     let name = sd.name;
-    let width = (visualize_cell::START_CELL_Y_SIZE * 4.0) as i32;
-    let height = (visualize_cell::START_CELL_X_SIZE * 4.0) as i32;
+    let width = (START_CELL_Y_SIZE * 4.0) as i32;
+    let height = (START_CELL_X_SIZE * 4.0) as i32;
     let fontsize = height as f64 / 8.0;
     let spacing = height as f64 / 40.0;
     let surface = cairo::ImageSurface::create(Format::Rgb24.into(), width, height).unwrap();
@@ -179,8 +210,8 @@ fn make_image_int(
 
     let mut file = File::create(&path).expect("Couldn't create 'file.png'");
     match surface.write_to_png(&mut file) {
-        Ok(_) => print!("."),
-        Err(_) => println!("Error create file.png"),
+        Ok(_) => tracing::debug!(?path, "image written"),
+        Err(e) => tracing::error!("Error writing {:?}: {:?}", path, e),
     }
 }
 
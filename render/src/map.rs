@@ -0,0 +1,106 @@
+use std::{collections::HashMap, fs::File, io, path::Path};
+
+use cairo::Format;
+
+use sim::{
+    celldata::{self, CellState, CellStateData, CellStateVariant},
+    hexgrid::{self, Pos, XYCont, CHUNK_SIZE},
+    logistics_plane::LogisticsState,
+    resource::{self, Resource},
+    GameState,
+};
+
+use super::Myrgb;
+
+// share of the logistics colour mixed into a cell when the overlay is on
+const OVERLAY_ALPHA: f64 = 0.5;
+const SOURCE_COLOR: (f64, f64, f64) = (1.0, 1.0, 1.0);
+const AVAILABLE_COLOR: (f64, f64, f64) = (1.0, 0.9, 0.2);
+
+pub type ColorCache = HashMap<CellStateVariant, Myrgb>;
+
+// one pixel per cell, covering every chunk generated so far
+pub fn export_map(g: &GameState, path: &Path, with_logistics: bool) -> io::Result<()> {
+    let chunks: Vec<Pos> = hexgrid::loaded_chunks(&g.matrix).collect();
+    let (Some(min_x), Some(max_x), Some(min_y), Some(max_y)) = (
+        chunks.iter().map(|p| p.x).min(),
+        chunks.iter().map(|p| p.x).max(),
+        chunks.iter().map(|p| p.y).min(),
+        chunks.iter().map(|p| p.y).max(),
+    ) else {
+        tracing::warn!("no chunks generated, nothing to export");
+        return Ok(());
+    };
+    let chunk = CHUNK_SIZE as i32;
+    let width = max_x + chunk - min_x;
+    let height = max_y + chunk - min_y;
+    let mut surface =
+        cairo::ImageSurface::create(Format::Rgb24.into(), width, height).map_err(cairo_error)?;
+    let stride = surface.stride() as usize;
+    let colors = all_variant_colors();
+    {
+        let mut data = surface.data().map_err(cairo_error)?;
+        for chunk_key in chunks {
+            for dx in 0..chunk {
+                for dy in 0..chunk {
+                    let p = chunk_key + XYCont { x: dx, y: dy };
+                    let cell = hexgrid::unsafe_get(p, &g.matrix);
+                    let mut color = cell_color(cell, &colors);
+                    if with_logistics {
+                        color = overlay(color, hexgrid::unsafe_get(p, &g.logistics_plane));
+                    }
+                    let index = (p.y - min_y) as usize * stride + (p.x - min_x) as usize * 4;
+                    data[index..index + 4].copy_from_slice(&to_pixel(color));
+                }
+            }
+        }
+    }
+    let mut file = File::create(path)?;
+    surface.write_to_png(&mut file).map_err(cairo_error)?;
+    tracing::info!("wrote {:?} ({}x{})", path, width, height);
+    Ok(())
+}
+
+// cairo's errors aren't all Send + Sync, keep their message only
+fn cairo_error(e: impl std::fmt::Display) -> io::Error {
+    io::Error::other(e.to_string())
+}
+
+// background colours of the tile images, read up front so lookups need no file access
+pub fn all_variant_colors() -> ColorCache {
+    enum_iterator::all::<CellStateVariant>()
+        .map(|cv| (cv, super::get_color_pair(celldata::unit_state(cv)).0))
+        .collect()
+}
+
+// same colours as the tile images, darker the less resources the cell holds
+pub fn cell_color(c: CellState, colors: &ColorCache) -> (f64, f64, f64) {
+    let base = colors[&c.variant];
+    let density = match c.data {
+        CellStateData::Resource(Resource::Pure(r))
+        | CellStateData::Resource(Resource::WithVariant(r, _)) => resource::fill_ratio(r),
+        _ => 1.0,
+    };
+    let shade = 0.35 + 0.65 * density;
+    (base.red * shade, base.green * shade, base.blue * shade)
+}
+
+fn overlay(color: (f64, f64, f64), l: LogisticsState) -> (f64, f64, f64) {
+    let top = match l {
+        LogisticsState::None => return color,
+        LogisticsState::Source => SOURCE_COLOR,
+        LogisticsState::Available(_) => AVAILABLE_COLOR,
+    };
+    let mix = |a: f64, b: f64| a * (1.0 - OVERLAY_ALPHA) + b * OVERLAY_ALPHA;
+    (
+        mix(color.0, top.0),
+        mix(color.1, top.1),
+        mix(color.2, top.2),
+    )
+}
+
+// Rgb24 is a native endian u32 per pixel, with the top byte unused
+fn to_pixel((r, g, b): (f64, f64, f64)) -> [u8; 4] {
+    let channel = |v: f64| (v.clamp(0.0, 1.0) * 255.0).round() as u32;
+    ((channel(r) << 16) | (channel(g) << 8) | channel(b)).to_ne_bytes()
+}
@@ -0,0 +1,165 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+
+use sim::{
+    actionmachine,
+    celldata::{self, CellStateVariant},
+    components,
+    hexgrid::{self, Pos, XYCont},
+    logistics_plane, make_world, new_game, GameState,
+};
+
+// Criterion benchmarks of the simulation: cargo bench -p sim [filter]
+criterion_group!(
+    benches,
+    get_set,
+    hot_clusters,
+    road_networks,
+    turns,
+    world_gen
+);
+criterion_main!(benches);
+
+const SEED: u32 = 1234;
+const ORIGIN: Pos = XYCont { x: 0, y: 0 };
+
+// a game with every chunk around the origin generated, so only the benchmarked work is timed
+fn loaded_game() -> GameState {
+    let mut g = new_game(make_world::new_with_seed(SEED));
+    let chunk = hexgrid::CHUNK_SIZE as i32;
+    hexgrid::touch_all_chunks(
+        &mut g.matrix,
+        XYCont {
+            x: -chunk,
+            y: -chunk,
+        },
+        2 * chunk - 1,
+        2 * chunk - 1,
+    );
+    g
+}
+
+fn hot() -> celldata::CellState {
+    celldata::new(
+        CellStateVariant::Hot,
+        celldata::CellStateData::Slot {
+            slot: celldata::Slot::Empty,
+        },
+    )
+}
+
+// every cell within radius of the origin made Hot
+fn with_hot_cluster(mut g: GameState, radius: i32) -> GameState {
+    for p in hexgrid::spiral(ORIGIN, radius) {
+        components::set(p, hot(), &mut g);
+    }
+    g
+}
+
+fn get_set(c: &mut Criterion) {
+    let mut g = loaded_game();
+    // the origin is where four chunks meet
+    let cells: Vec<Pos> = hexgrid::spiral(ORIGIN, 20).collect();
+    let mut group = c.benchmark_group("hexgrid");
+    group.bench_function("get across chunk seams", |b| {
+        b.iter(|| {
+            for p in cells.iter() {
+                criterion::black_box(hexgrid::get(*p, &mut g.matrix));
+            }
+        })
+    });
+    group.bench_function("set across chunk seams", |b| {
+        let cell = celldata::unit_state(CellStateVariant::Unused);
+        b.iter(|| {
+            for p in cells.iter() {
+                hexgrid::set(*p, cell, &mut g.matrix);
+            }
+        })
+    });
+    // the first write after a clone copies the chunk
+    group.bench_function("set after clone", |b| {
+        let cell = celldata::unit_state(CellStateVariant::Unused);
+        b.iter_batched(
+            || g.clone(),
+            |mut g1| {
+                hexgrid::set(ORIGIN, cell, &mut g1.matrix);
+                g1
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+fn hot_clusters(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hot cluster");
+    for radius in [5, 15, 30] {
+        let mut g = with_hot_cluster(loaded_game(), radius);
+        group.bench_with_input(BenchmarkId::new("flood", radius), &radius, |b, _| {
+            b.iter(|| {
+                hexgrid::flood(
+                    ORIGIN,
+                    |c: &celldata::CellState| c.variant == CellStateVariant::Hot,
+                    |_| false,
+                    None,
+                    &mut g.matrix,
+                )
+            })
+        });
+        components::connected(components::Kind::Hot, ORIGIN, &mut g);
+        group.bench_with_input(BenchmarkId::new("cached", radius), &radius, |b, _| {
+            b.iter(|| components::connected(components::Kind::Hot, ORIGIN, &mut g))
+        });
+    }
+    group.finish();
+}
+
+fn road_networks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("update_logistics");
+    group.sample_size(10);
+    for length in [10, 50, 200] {
+        // new_game starts with a hub at the origin
+        let mut g = loaded_game();
+        let end = XYCont { x: 0, y: length };
+        for p in hexgrid::line(XYCont { x: 0, y: 1 }, end) {
+            components::set(p, celldata::unit_state(CellStateVariant::Road), &mut g);
+        }
+        group.bench_with_input(BenchmarkId::new("road", length), &length, |b, _| {
+            b.iter(|| logistics_plane::update_logistics(end, false, g.clone()))
+        });
+    }
+    group.finish();
+}
+
+fn turns(c: &mut Criterion) {
+    let mut group = c.benchmark_group("actionmachine::run");
+    group.sample_size(10);
+    for radius in [10, 30] {
+        let mut g = with_hot_cluster(loaded_game(), radius);
+        for p in hexgrid::spiral(ORIGIN, radius) {
+            let cell = actionmachine::new_in_progress(CellStateVariant::Hot, 5);
+            hexgrid::set(p, cell, &mut g.matrix);
+            g.action_machine = actionmachine::maybe_insert(g.action_machine, p, cell.variant);
+        }
+        let cells = hexgrid::spiral(ORIGIN, radius).count();
+        group.bench_with_input(BenchmarkId::new("hot cells", cells), &cells, |b, _| {
+            b.iter(|| actionmachine::run(g.clone()))
+        });
+    }
+    group.finish();
+}
+
+fn world_gen(c: &mut Criterion) {
+    let mut group = c.benchmark_group("make_world");
+    group.sample_size(10);
+    group.bench_function("new chunk", |b| {
+        b.iter_batched(
+            || make_world::new_with_seed(SEED),
+            |mut board| {
+                hexgrid::get(ORIGIN, &mut board);
+                board
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
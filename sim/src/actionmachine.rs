@@ -0,0 +1,319 @@
+use std::collections::HashMap;
+
+use crate::{
+    build_queue, building,
+    celldata::{self, CellState, CellStateData, CellStateVariant},
+    components,
+    error::GameError,
+    events::{self, GameEvent},
+    hexgrid, logistics_plane, resource, stats, GameState,
+};
+
+//crontab but for game triggers
+// persistent sets, so a clone shares everything with the original until either changes
+pub type ActionMachine = [im::HashSet<hexgrid::Pos>; ACTION_MAX_PRIO];
+
+pub type Prio = usize;
+pub const ACTION_MAX_PRIO: Prio = CellStateVariant::Last as Prio;
+
+pub type InProgressWait = u32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InProgress {
+    Pure(InProgressWait),
+    WithOther(InProgressWait, Other),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Other {
+    CellStateVariant(CellStateVariant),
+    CvAndRS(CellStateVariant, resource::ResourceStockpile),
+}
+
+// for now the main point of prio is to ensure
+// all CellStateVariants which are the same are executed after eachother
+// to limit effects of order added on order executed
+pub fn prio(cv: celldata::CellStateVariant) -> Option<Prio> {
+    match cv {
+        CellStateVariant::WoodFarm
+        | CellStateVariant::Hot
+        | CellStateVariant::Feeder
+        | CellStateVariant::Seller
+        | CellStateVariant::Building => Some(cv as usize),
+        _ => None,
+    }
+}
+
+pub fn new() -> ActionMachine {
+    Default::default()
+}
+
+pub fn new_in_progress(cv: CellStateVariant, wait: InProgressWait) -> CellState {
+    CellState {
+        variant: cv,
+        data: CellStateData::InProgress(InProgress::Pure(wait)),
+    }
+}
+
+pub fn new_in_progress_with_variant(
+    cv: CellStateVariant,
+    wait: InProgressWait,
+    cv2: CellStateVariant,
+) -> CellState {
+    new_in_progress_with_other(cv, wait, Other::CellStateVariant(cv2))
+}
+
+pub fn new_in_progress_with_variant_and_resource(
+    cv: CellStateVariant,
+    wait: InProgressWait,
+    cv2: CellStateVariant,
+    res: resource::ResourceStockpile,
+) -> CellState {
+    new_in_progress_with_other(cv, wait, Other::CvAndRS(cv2, res))
+}
+
+fn new_in_progress_with_other(cv: CellStateVariant, wait: InProgressWait, oth: Other) -> CellState {
+    CellState {
+        variant: cv,
+        data: CellStateData::InProgress(InProgress::WithOther(wait, oth)),
+    }
+}
+
+pub fn maybe_insert(
+    mut m: ActionMachine,
+    pos: hexgrid::Pos,
+    cv: celldata::CellStateVariant,
+) -> ActionMachine {
+    if let Some(p) = prio(cv) {
+        m[p].insert(pos);
+    }
+    m
+}
+pub fn remove(
+    mut m: ActionMachine,
+    pos: hexgrid::Pos,
+    cv: celldata::CellStateVariant,
+) -> ActionMachine {
+    if let Some(p) = prio(cv) {
+        m[p].remove(&pos);
+    }
+    m
+}
+
+// None for variants that never wait on anything
+pub fn in_progress_max(cv: celldata::CellStateVariant) -> Option<InProgressWait> {
+    match cv {
+        celldata::CellStateVariant::WoodFarm => Some(3),
+        celldata::CellStateVariant::Hot => Some(5),
+        celldata::CellStateVariant::Building => Some(building::max_buildtime()),
+        _ => None,
+    }
+}
+
+fn do_in_progress(
+    p: hexgrid::Pos,
+    cv: CellStateVariant,
+    ip: InProgress,
+    mut g: GameState,
+) -> Result<GameState, GameError> {
+    match ip {
+        InProgress::Pure(1) => do_pure_progress_done(p, cv, g),
+        InProgress::WithOther(1, cv2) => do_progress_done_other(p, cv, cv2, g),
+        InProgress::Pure(x) => {
+            hexgrid::set(p, new_in_progress(cv, x - 1), &mut g.matrix);
+            Ok(g)
+        }
+        InProgress::WithOther(x, cv2) => {
+            hexgrid::set(p, new_in_progress_with_other(cv, x - 1, cv2), &mut g.matrix);
+            Ok(g)
+        }
+    }
+}
+
+fn do_pure_progress_done(
+    p: hexgrid::Pos,
+    cv: CellStateVariant,
+    mut g: GameState,
+) -> Result<GameState, GameError> {
+    match cv {
+        celldata::CellStateVariant::WoodFarm => {
+            let wait = in_progress_max(cv)
+                .ok_or_else(|| GameError::UnexpectedCell(p, hexgrid::unsafe_get(p, &g.matrix)))?;
+            g = logistics_plane::return_lp(p, g)?;
+            let packet =
+                resource::from_key_value(HashMap::from([(resource::ResourceType::Wood, -10)]));
+            // a full hub just means the farm waits another turn
+            match logistics_plane::try_take_resources(p, packet, &mut g) {
+                Ok(g1) => {
+                    g = g1;
+                    let new_cell = new_in_progress(cv, wait);
+                    hexgrid::set(p, new_cell, &mut g.matrix);
+                    events::clear_stuck(p, &mut g);
+                }
+                Err(GameError::NoCapacity(..)) => {
+                    events::push_stuck(GameEvent::HubFull(cv, p), &mut g)
+                }
+                // the hubs have room but not the logistics points to reach the farm right now
+                Err(GameError::NotEnoughResources(..)) => {}
+                Err(e) => return Err(e),
+            }
+            Ok(g)
+        }
+        celldata::CellStateVariant::Hot => {
+            let new_cell = celldata::CellState {
+                variant: cv,
+                data: celldata::CellStateData::Slot {
+                    slot: celldata::Slot::Done,
+                },
+            };
+            hexgrid::set(p, new_cell, &mut g.matrix);
+            events::push(GameEvent::HotDone(p), &mut g);
+            Ok(g)
+        }
+        _ => Err(GameError::UnexpectedCell(p, hexgrid::get(p, &mut g.matrix))),
+    }
+}
+
+fn do_progress_done_other(
+    p: hexgrid::Pos,
+    cv: celldata::CellStateVariant,
+    oth: Other,
+    mut g: GameState,
+) -> Result<GameState, GameError> {
+    match cv {
+        celldata::CellStateVariant::Building => building::finalize_build(oth, p, g),
+        _ => Err(GameError::UnexpectedCell(p, hexgrid::get(p, &mut g.matrix))),
+    }
+}
+
+// the Hot cluster next to p, cells in the order they were found
+fn connected_hot(p: hexgrid::Pos, g: &mut GameState) -> Vec<(hexgrid::Pos, CellState)> {
+    components::connected(components::Kind::Hot, p, g)
+        .into_iter()
+        .map(|hp| (hp, hexgrid::unsafe_get(hp, &g.matrix)))
+        .collect()
+}
+
+fn do_tick(
+    p: hexgrid::Pos,
+    c: celldata::CellState,
+    mut g: GameState,
+) -> Result<GameState, GameError> {
+    match c {
+        celldata::CellState {
+            variant,
+            data: celldata::CellStateData::InProgress(in_progress),
+        } => {
+            g = do_in_progress(p, variant, in_progress, g)?;
+        }
+        celldata::CellState {
+            variant: celldata::CellStateVariant::Feeder,
+            ..
+        } => {
+            let con: Vec<(hexgrid::Pos, celldata::CellState)> = connected_hot(p, &mut g)
+                .into_iter()
+                .filter(|(_p, i)| {
+                    matches!(
+                        i,
+                        celldata::CellState {
+                            variant: celldata::CellStateVariant::Hot,
+                            data: celldata::CellStateData::Slot {
+                                slot: celldata::Slot::Empty,
+                            },
+                        }
+                    )
+                })
+                .collect();
+            if let Some((
+                hp,
+                celldata::CellState {
+                    variant: celldata::CellStateVariant::Hot,
+                    data:
+                        celldata::CellStateData::Slot {
+                            slot: celldata::Slot::Empty,
+                        },
+                    ..
+                },
+            )) = con.first()
+            {
+                let cv = celldata::CellStateVariant::Hot;
+                let wait =
+                    in_progress_max(cv).ok_or_else(|| GameError::UnexpectedCell(*hp, con[0].1))?;
+                let new_cell = new_in_progress(cv, wait);
+                hexgrid::set(*hp, new_cell, &mut g.matrix);
+            }
+        }
+        celldata::CellState {
+            variant: celldata::CellStateVariant::Seller,
+            ..
+        } => {
+            let con: Vec<(hexgrid::Pos, celldata::CellState)> = connected_hot(p, &mut g)
+                .into_iter()
+                .filter(|(_p, i)| {
+                    matches!(
+                        i,
+                        celldata::CellState {
+                            variant: celldata::CellStateVariant::Hot,
+                            data: celldata::CellStateData::Slot {
+                                slot: celldata::Slot::Done,
+                            },
+                        }
+                    )
+                })
+                .collect();
+            if let Some((
+                hp,
+                celldata::CellState {
+                    variant: celldata::CellStateVariant::Hot,
+                    data:
+                        celldata::CellStateData::Slot {
+                            slot: celldata::Slot::Done,
+                        },
+                },
+            )) = con.first()
+            {
+                let new_cell = celldata::CellState {
+                    variant: celldata::CellStateVariant::Hot,
+                    data: celldata::CellStateData::Slot {
+                        slot: celldata::Slot::Empty,
+                    },
+                };
+                hexgrid::set(*hp, new_cell, &mut g.matrix);
+                events::push(GameEvent::Sold(*hp), &mut g);
+                // TODO selling should make gold or something
+            }
+        }
+        celldata::CellState {
+            variant: celldata::CellStateVariant::Hot,
+            ..
+        } => {}
+        c @ celldata::CellState {
+            variant: celldata::CellStateVariant::Building,
+            data:
+                celldata::CellStateData::Resource(resource::Resource::WithVariant(resources, goal_cv)),
+        } => g = building::do_build_progress(c, p, resources, goal_cv, g)?,
+        a => return Err(GameError::UnexpectedCell(p, a)),
+    };
+    Ok(g)
+}
+
+// A turn either runs completely or not at all, on Err the caller still has the state it passed in
+pub fn run(g: GameState) -> Result<GameState, GameError> {
+    Ok(stats::record(run_cells(g)?))
+}
+
+// run without recording the statistics
+pub fn run_cells(mut g: GameState) -> Result<GameState, GameError> {
+    let _span = tracing::debug_span!("turn", turn = g.turn).entered();
+    let old_acton_machine = g.action_machine.clone();
+    for v in old_acton_machine.into_iter() {
+        g = v.into_iter().try_fold(g, |mut acc, pos| {
+            let cell = hexgrid::get(pos, &mut acc.matrix);
+            do_tick(pos, cell, acc)
+        })?
+    }
+    // blueprints start last, in queue order, what they start is first run next turn
+    g = build_queue::tick(g);
+    g.turn += 1;
+    Ok(g)
+}
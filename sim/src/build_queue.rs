@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+use crate::{
+    building::{self, Blocker},
+    celldata::CellStateVariant,
+    events::{self, GameEvent},
+    hexgrid::Pos,
+    GameState,
+};
+
+// A building the player wants at pos, construction starts once a hub in range
+// has a free builder and the materials, see building::blocker.
+// The queue is in priority order, the first blueprint gets the builders first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Blueprint {
+    pub pos: Pos,
+    pub cv: CellStateVariant,
+    // what it was waiting for when last checked, shown on its ghost on the board
+    pub blocker: Option<Blocker>,
+}
+
+// persistent like the rest of the game state, so a clone shares it until either changes
+pub type BuildQueue = im::Vector<Blueprint>;
+
+pub fn new() -> BuildQueue {
+    im::Vector::new()
+}
+
+// one blueprint per cell, placing another replaces it but keeps its place in the queue
+pub fn place(cv: CellStateVariant, pos: Pos, mut g: GameState) -> GameState {
+    let b = Blueprint {
+        pos,
+        cv,
+        blocker: building::blocker(cv, pos, &g),
+    };
+    match g.build_queue.iter().position(|i| i.pos == pos) {
+        Some(index) => g.build_queue[index] = b,
+        None => g.build_queue.push_back(b),
+    }
+    g
+}
+
+pub fn remove(index: usize, mut g: GameState) -> GameState {
+    if index < g.build_queue.len() {
+        g.build_queue.remove(index);
+    }
+    g
+}
+
+pub fn move_up(index: usize, mut g: GameState) -> GameState {
+    if index > 0 && index < g.build_queue.len() {
+        g.build_queue.swap(index - 1, index);
+    }
+    g
+}
+
+pub fn move_down(index: usize, mut g: GameState) -> GameState {
+    if index + 1 < g.build_queue.len() {
+        g.build_queue.swap(index, index + 1);
+    }
+    g
+}
+
+// place in the queue, starting at 1
+pub fn queued_at(pos: Pos, g: &GameState) -> Option<(usize, Blueprint)> {
+    g.build_queue
+        .iter()
+        .enumerate()
+        .find(|(_, b)| b.pos == pos)
+        .map(|(i, b)| (i + 1, *b))
+}
+
+// queued_at for every blueprint at once, for looking up many cells
+pub fn by_pos(g: &GameState) -> HashMap<Pos, (usize, Blueprint)> {
+    g.build_queue
+        .iter()
+        .enumerate()
+        .map(|(i, b)| (b.pos, (i + 1, *b)))
+        .collect()
+}
+
+// starts whatever can be started, in queue order.
+// A blueprint whose cell no longer offers its building is dropped,
+// the others remember what they wait for and are tried again next turn
+pub fn tick(mut g: GameState) -> GameState {
+    for b in g.build_queue.clone() {
+        let Some(index) = g.build_queue.iter().position(|i| i.pos == b.pos) else {
+            continue;
+        };
+        match building::blocker(b.cv, b.pos, &g) {
+            None => {
+                if let Ok(g1) = building::build(b.cv, b.pos, g.clone()) {
+                    g = remove(index, g1);
+                }
+            }
+            Some(Blocker::WrongCell) => {
+                g = remove(index, g);
+                events::push(GameEvent::BlueprintDropped(b.cv, b.pos), &mut g);
+            }
+            blocker => g.build_queue[index].blocker = blocker,
+        }
+    }
+    g
+}
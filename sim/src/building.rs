@@ -0,0 +1,326 @@
+use std::{cmp::min, collections::HashMap, fmt};
+
+use crate::{
+    actionmachine::{self},
+    celldata::{self, CellState, CellStateData, CellStateVariant},
+    components,
+    error::GameError,
+    events::{self, GameEvent},
+    hexgrid::{self},
+    logistics_plane::{self, LogisticsState},
+    menu, resource, GameState,
+};
+
+fn has_buildtime() -> Vec<CellStateVariant> {
+    enum_iterator::all::<CellStateVariant>()
+        .filter(|i| buildtime(*i).is_some())
+        .collect()
+}
+
+fn buildtime(cv: CellStateVariant) -> Option<actionmachine::InProgressWait> {
+    match cv {
+        CellStateVariant::Hot => Some(4),
+        CellStateVariant::Feeder => Some(1),
+        CellStateVariant::Seller => Some(1),
+        CellStateVariant::Insulation => Some(2),
+        CellStateVariant::WoodFarm => Some(3),
+        CellStateVariant::WoodCutter => Some(1),
+        CellStateVariant::Road => Some(1),
+        _ => None,
+    }
+}
+
+fn buildtime_keep_res(cv: CellStateVariant) -> Option<actionmachine::InProgressWait> {
+    match cv {
+        CellStateVariant::Unused => Some(2),
+        _ => None,
+    }
+}
+
+fn buildcost_cell(cv: CellStateVariant) -> Option<CellState> {
+    match cv {
+        to @ CellStateVariant::Hub => Some(resource::new_stockpile(
+            CellStateVariant::Building,
+            HashMap::from([(resource::ResourceType::Builders, 1)]),
+            to,
+        )),
+        _ => None,
+    }
+}
+
+pub fn required_per_build_action(_cv: CellStateVariant) -> resource::ResourcePacket {
+    resource::from_key_value(HashMap::from([(resource::ResourceType::Wood, 10)]))
+}
+pub fn build_action_req(_cv: CellStateVariant) -> resource::ResourceValue {
+    10
+}
+
+pub fn use_builder(pos: hexgrid::Pos, mut g: GameState) -> Result<GameState, GameError> {
+    let p = resource::new_packet(1, 0);
+    logistics_plane::try_borrow_resources(pos, p, &mut g)
+}
+
+pub fn do_build_progress(
+    mut c: CellState,
+    p: hexgrid::Pos,
+    r: resource::ResourceStockpile,
+    cv2: celldata::CellStateVariant,
+    mut g: GameState,
+) -> Result<GameState, GameError> {
+    g = logistics_plane::return_lp(p, g)?;
+    let builders = resource::get(resource::ResourceType::Builders, r);
+    let req = required_per_build_action(cv2);
+    let done_threshold = build_action_req(cv2);
+    let pre_progress = resource::get(resource::ResourceType::BuildTime, r);
+    let work_left = done_threshold - pre_progress;
+    let mut progress = 0;
+    for _ in 0..min(builders, work_left) {
+        if let Ok(g1) = logistics_plane::try_take_resources(p, req, &mut g) {
+            g = g1;
+            progress += 1
+        } else {
+            break;
+        }
+    }
+    if progress == 0 && work_left > 0 {
+        events::push_stuck(GameEvent::BuildStalled(cv2, p), &mut g);
+    } else {
+        events::clear_stuck(p, &mut g);
+    }
+    if progress == builders {
+        if let Ok(g1) = logistics_plane::try_borrow_resources(p, resource::new_packet(1, 0), &mut g)
+        {
+            if let Some(c1) = resource::add(resource::ResourceType::Builders, c, 1) {
+                c = c1;
+                g = g1;
+            }
+        }
+    }
+    if (progress + pre_progress) == done_threshold {
+        finalize_build(actionmachine::Other::CellStateVariant(cv2), p, g)
+    } else {
+        let c1 = resource::add(resource::ResourceType::BuildTime, c, progress)
+            .ok_or(GameError::NoCapacity(p, c))?;
+        tracing::trace!(?p, progress, ?c1, "build progress");
+        hexgrid::set(p, c1, &mut g.matrix);
+        Ok(g)
+    }
+}
+
+pub fn max_buildtime() -> actionmachine::InProgressWait {
+    has_buildtime()
+        .into_iter()
+        .filter_map(buildtime)
+        .max()
+        .unwrap()
+}
+
+// What a single build of cv takes. Builders are only lent and come back once it is done
+pub struct BuildCost {
+    pub builder_turns: i32,
+    pub materials: resource::ResourcePacket,
+}
+
+pub fn cost(cv: CellStateVariant) -> BuildCost {
+    if let Some(b) = buildtime(cv).or(buildtime_keep_res(cv)) {
+        BuildCost {
+            builder_turns: b as i32,
+            materials: resource::empty_packet(),
+        }
+    } else if buildcost_cell(cv).is_some() {
+        // every build action takes one builder for a turn and the materials for that action
+        let actions = build_action_req(cv);
+        let per_action = required_per_build_action(cv);
+        BuildCost {
+            builder_turns: actions,
+            materials: (0..actions).fold(resource::empty_packet(), |acc, _| {
+                resource::add_packet_to_packet(acc, per_action)
+            }),
+        }
+    } else {
+        BuildCost {
+            builder_turns: 0,
+            materials: resource::empty_packet(),
+        }
+    }
+}
+
+// what keeps a building from being started at a cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Blocker {
+    // the cell doesn't offer the building (any more), waiting won't change that
+    WrongCell,
+    // no hub reaches the cell
+    NotConnected,
+    // hubs reach it, but none has a builder to spare
+    NoBuilder,
+    // for buildings built from materials, no hub in reach has those for the first build action
+    NoMaterials,
+}
+
+impl fmt::Display for Blocker {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Blocker::WrongCell => write!(f, "can't be built here"),
+            Blocker::NotConnected => write!(f, "no hub in reach"),
+            Blocker::NoBuilder => write!(f, "waiting for a builder"),
+            Blocker::NoMaterials => write!(f, "waiting for materials"),
+        }
+    }
+}
+
+// None if cv can be started at pos right now
+pub fn blocker(cv: CellStateVariant, pos: hexgrid::Pos, g: &GameState) -> Option<Blocker> {
+    let c = hexgrid::unsafe_get(pos, &g.matrix);
+    if !menu::options(c).is_some_and(|actions| actions.contains(&cv)) {
+        return Some(Blocker::WrongCell);
+    }
+    let sources = logistics_plane::connected_sources(pos, g);
+    if sources.is_empty() {
+        return Some(Blocker::NotConnected);
+    }
+    // every build, with or without buildtime, lends a builder from a hub first
+    if !logistics_plane::has_worker(pos, g) {
+        return Some(Blocker::NoBuilder);
+    }
+    if buildcost_cell(cv).is_some() {
+        let req = required_per_build_action(cv);
+        let has_materials =
+            sources
+                .into_iter()
+                .any(|h| match hexgrid::unsafe_get(h, &g.matrix).data {
+                    CellStateData::Resource(resource::Resource::Pure(r)) => {
+                        resource::has_resources(req, r)
+                    }
+                    _ => false,
+                });
+        if !has_materials {
+            return Some(Blocker::NoMaterials);
+        }
+    }
+    None
+}
+
+pub fn can_start(cv: CellStateVariant, pos: hexgrid::Pos, g: &GameState) -> bool {
+    blocker(cv, pos, g).is_none()
+}
+
+// On Err nothing was changed, g is simply dropped and the caller keeps its own copy
+pub fn build(
+    cv: CellStateVariant,
+    pos: hexgrid::Pos,
+    mut g: GameState,
+) -> Result<GameState, GameError> {
+    // whatever was stuck at pos is replaced
+    events::clear_stuck(pos, &mut g);
+    if let Some(new_cell) = menu::transition(cv, pos, &mut g) {
+        components::set(pos, new_cell, &mut g);
+        Ok(g)
+    } else {
+        let new_cell = if let Some(b) = buildtime(cv) {
+            actionmachine::new_in_progress_with_variant(CellStateVariant::Building, b, cv)
+        } else if let Some(new_cell) = buildcost_cell(cv) {
+            new_cell
+        } else if let Some(b) = buildtime_keep_res(cv) {
+            let old_res = match hexgrid::get(pos, &mut g.matrix) {
+                CellState {
+                    variant: CellStateVariant::Hidden,
+                    data: CellStateData::Resource(resource::Resource::Pure(res)),
+                } => res,
+                a => return Err(GameError::UnexpectedCell(pos, a)),
+            };
+            actionmachine::new_in_progress_with_variant_and_resource(
+                CellStateVariant::Building,
+                b,
+                cv,
+                old_res,
+            )
+        } else {
+            return Err(GameError::CannotBuild(cv, pos));
+        };
+        g = use_builder(pos, g)?;
+        g.action_machine =
+            actionmachine::maybe_insert(g.action_machine, pos, CellStateVariant::Building);
+        components::set(pos, new_cell, &mut g);
+        events::push(GameEvent::BuildStarted(cv, pos), &mut g);
+        Ok(g)
+    }
+}
+
+pub fn finalize_build(
+    oth: actionmachine::Other,
+    pos: hexgrid::Pos,
+    mut g: GameState,
+) -> Result<GameState, GameError> {
+    g = logistics_plane::return_borrows(pos, g)?;
+    g = logistics_plane::return_lp(pos, g)?;
+    g = do_build(oth, pos, g)?;
+    let cv = match oth {
+        actionmachine::Other::CellStateVariant(cv) => cv,
+        actionmachine::Other::CvAndRS(cv, _) => cv,
+    };
+    events::push(GameEvent::BuildFinished(cv, pos), &mut g);
+    Ok(g)
+}
+
+pub fn do_build(
+    oth: actionmachine::Other,
+    pos: hexgrid::Pos,
+    mut g: GameState,
+) -> Result<GameState, GameError> {
+    let cv = match oth {
+        actionmachine::Other::CellStateVariant(cv) => cv,
+        actionmachine::Other::CvAndRS(cv, _) => cv,
+    };
+    g.action_machine = actionmachine::remove(g.action_machine, pos, CellStateVariant::Building);
+    g.action_machine = actionmachine::maybe_insert(g.action_machine, pos, cv);
+    let new_cell = match cv {
+        a @ (CellStateVariant::Insulation
+        | CellStateVariant::Feeder
+        | CellStateVariant::Seller
+        | CellStateVariant::Road) => celldata::unit_state(a),
+        CellStateVariant::Unused => {
+            let res = match oth {
+                actionmachine::Other::CvAndRS(_, res) => res,
+                _ => return Err(GameError::CannotBuild(cv, pos)),
+            };
+            resource::new_pure_stockpile(cv, resource::to_key_value(res))
+        }
+        CellStateVariant::WoodCutter => {
+            let res = match oth {
+                actionmachine::Other::CvAndRS(_, res) => res,
+                _ => return Err(GameError::CannotBuild(cv, pos)),
+            };
+            resource::new_pure_stockpile(cv, resource::to_key_value(res))
+        }
+        CellStateVariant::Hot => CellState {
+            variant: cv,
+            data: celldata::CellStateData::Slot {
+                slot: celldata::Slot::Empty,
+            },
+        },
+        a @ CellStateVariant::WoodFarm => actionmachine::new_in_progress(a, 3),
+        CellStateVariant::Hub => {
+            let new_ls_cell = LogisticsState::Source;
+            hexgrid::set(pos, new_ls_cell, &mut g.logistics_plane);
+            resource::new_hub()
+        }
+        _ => return Err(GameError::CannotBuild(cv, pos)),
+    };
+    tracing::debug!(?pos, ?new_cell, "built");
+    components::set(pos, new_cell, &mut g);
+    if cv == CellStateVariant::Hub {
+        g = logistics_plane::update_logistics(pos, true, g);
+    }
+    if cv == CellStateVariant::Road {
+        g = logistics_plane::update_logistics(pos, false, g);
+    }
+    if let Some(new_delta) = celldata::leak_delta(cv, pos, &mut g.matrix) {
+        g.resources.leak += new_delta;
+        g.resources.heat_efficency = g.resources.tiles as f64 / g.resources.leak as f64;
+    }
+    if celldata::is_hot_v(cv) {
+        g.resources.tiles += 1;
+    }
+    Ok(g)
+}
@@ -12,13 +12,13 @@ use crate::{actionmachine, hexgrid, resource};
 // and CellState -> CellStateData
 // Seems very boilerplatey
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub(crate) struct CellState {
-    pub(crate) variant: CellStateVariant,
-    pub(crate) data: CellStateData,
+pub struct CellState {
+    pub variant: CellStateVariant,
+    pub data: CellStateData,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub(crate) enum CellStateData {
+pub enum CellStateData {
     Unit,
     Slot { slot: Slot },
     InProgress(actionmachine::InProgress),
@@ -26,12 +26,12 @@ pub(crate) enum CellStateData {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub(crate) enum Slot {
+pub enum Slot {
     Empty,
     Done,
 }
 
-pub(crate) fn unit_state(cv: CellStateVariant) -> CellState {
+pub fn unit_state(cv: CellStateVariant) -> CellState {
     CellState {
         data: CellStateData::Unit,
         variant: cv,
@@ -39,7 +39,7 @@ pub(crate) fn unit_state(cv: CellStateVariant) -> CellState {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Sequence)]
-pub(crate) enum CellStateVariant {
+pub enum CellStateVariant {
     Menu,
     Hidden,
     Unused,
@@ -61,11 +61,9 @@ pub(crate) enum CellStateVariant {
     Last, //NEEDS TO EXIST AND BE LAST
 }
 
-impl Into<CellStateVariant> for CellState {
-    fn into(self) -> CellStateVariant {
-        match self {
-            CellState { variant, .. } => variant,
-        }
+impl From<CellState> for CellStateVariant {
+    fn from(c: CellState) -> Self {
+        c.variant
     }
 }
 
@@ -75,28 +73,17 @@ impl fmt::Display for CellStateVariant {
     }
 }
 
-pub(crate) fn new(cv: CellStateVariant, data: CellStateData) -> CellState {
+pub fn new(cv: CellStateVariant, data: CellStateData) -> CellState {
     CellState { variant: cv, data }
 }
 
-pub(crate) fn is_hot(c: CellState) -> bool {
-    let cv: CellStateVariant = c.into();
-    is_hot_v(cv)
+pub fn is_hot_v(cv: CellStateVariant) -> bool {
+    matches!(cv, CellStateVariant::Hot)
 }
 
-pub(crate) fn is_hot_v(cv: CellStateVariant) -> bool {
+// base leak of cv and what each neighbour of a variant adds to it
+fn leak_effects(cv: CellStateVariant) -> Option<(i32, HashMap<CellStateVariant, i32>)> {
     match cv {
-        CellStateVariant::Hot => true,
-        _ => false,
-    }
-}
-
-pub(crate) fn leak_delta(
-    cv: CellStateVariant,
-    p: hexgrid::Pos,
-    m: &mut hexgrid::Board,
-) -> Option<i32> {
-    if let Some((base, n_effects)) = match cv {
         CellStateVariant::Insulation => Some((0, HashMap::from([(CellStateVariant::Hot, -1)]))),
         CellStateVariant::Hot => Some((
             12,
@@ -106,7 +93,15 @@ pub(crate) fn leak_delta(
             ]),
         )),
         _ => None,
-    } {
+    }
+}
+
+pub fn has_leak(cv: CellStateVariant) -> bool {
+    leak_effects(cv).is_some()
+}
+
+pub fn leak_delta(cv: CellStateVariant, p: hexgrid::Pos, m: &mut hexgrid::Board) -> Option<i32> {
+    if let Some((base, n_effects)) = leak_effects(cv) {
         let n_effects_applied: i32 = hexgrid::neighbors(p, m)
             .map(|(_, i)| {
                 let ct: CellStateVariant = i.into();
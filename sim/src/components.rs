@@ -0,0 +1,143 @@
+use std::sync::Arc;
+
+use crate::{
+    celldata::{self, CellState, CellStateVariant},
+    hexgrid::{self, Pos},
+    stats, GameState,
+};
+
+// Groups of cells that act together when they touch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Hot,
+    Road,
+    // hubs and the roads between them
+    Logistics,
+}
+
+const KINDS: [Kind; 3] = [Kind::Hot, Kind::Road, Kind::Logistics];
+
+pub fn is_member(kind: Kind, cv: CellStateVariant) -> bool {
+    match (kind, cv) {
+        (Kind::Hot, cv) => celldata::is_hot_v(cv),
+        (Kind::Road, CellStateVariant::Road) => true,
+        (Kind::Logistics, CellStateVariant::Hub | CellStateVariant::Road) => true,
+        _ => false,
+    }
+}
+
+// Components found so far, filled in as they are asked for.
+// Membership only depends on the variant, so a component stays valid
+// until a cell in or next to it joins or leaves the kind, see set
+#[derive(Debug, Clone, Default)]
+pub struct ComponentIndex {
+    kinds: [KindIndex; KINDS.len()],
+    // where the buildings of each variant are, see stats::is_building.
+    // Unlike the components this is always complete
+    buildings: im::HashMap<CellStateVariant, im::HashSet<Pos>>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct KindIndex {
+    component_of: im::HashMap<Pos, usize>,
+    components: im::HashMap<usize, Arc<Vec<Pos>>>,
+    next_id: usize,
+}
+
+pub fn new() -> ComponentIndex {
+    Default::default()
+}
+
+// every building of cv on the board
+pub fn buildings(cv: CellStateVariant, g: &GameState) -> impl Iterator<Item = Pos> + '_ {
+    g.components
+        .buildings
+        .get(&cv)
+        .into_iter()
+        .flat_map(|positions| positions.iter().cloned())
+}
+
+pub fn building_count(cv: CellStateVariant, g: &GameState) -> usize {
+    g.components
+        .buildings
+        .get(&cv)
+        .map_or(0, |positions| positions.len())
+}
+
+// every cell of kind touching p or connected to one that does, p included if it is one.
+// The same cells hexgrid::flood finds, grouped by component
+pub fn connected(kind: Kind, p: Pos, g: &mut GameState) -> Vec<Pos> {
+    let mut ids = vec![];
+    for q in [p].into_iter().chain(hexgrid::neighbor_pos(p)) {
+        if !is_member(kind, hexgrid::get_ref(q, &mut g.matrix).variant) {
+            continue;
+        }
+        let id = match g.components.kinds[kind as usize].component_of.get(&q) {
+            Some(id) => *id,
+            None => add_component(kind, q, g),
+        };
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    let index = &g.components.kinds[kind as usize];
+    ids.into_iter()
+        .flat_map(|id| index.components[&id].iter().cloned())
+        .collect()
+}
+
+fn add_component(kind: Kind, start: Pos, g: &mut GameState) -> usize {
+    let members: Vec<Pos> = hexgrid::flood(
+        start,
+        |c: &CellState| is_member(kind, c.variant),
+        |_| false,
+        None,
+        &mut g.matrix,
+    )
+    .into_iter()
+    .map(|(p, _)| p)
+    .collect();
+    let index = &mut g.components.kinds[kind as usize];
+    let id = index.next_id;
+    index.next_id += 1;
+    for p in members.iter() {
+        index.component_of.insert(*p, id);
+    }
+    index.components.insert(id, Arc::new(members));
+    id
+}
+
+// Every change of a cell's variant has to go through here, other changes can use hexgrid::set.
+// Only the components around p are dropped, they are found again the next time they are needed
+pub fn set(p: Pos, c: CellState, g: &mut GameState) {
+    let old = hexgrid::get_ref(p, &mut g.matrix).variant;
+    for kind in KINDS {
+        if is_member(kind, old) != is_member(kind, c.variant) {
+            let index = &mut g.components.kinds[kind as usize];
+            for q in [p].into_iter().chain(hexgrid::neighbor_pos(p)) {
+                if let Some(id) = index.component_of.get(&q).cloned() {
+                    if let Some(members) = index.components.remove(&id) {
+                        for m in members.iter() {
+                            index.component_of.remove(m);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    if old != c.variant {
+        if stats::is_building(old) {
+            if let Some(positions) = g.components.buildings.get_mut(&old) {
+                positions.remove(&p);
+            }
+        }
+        if stats::is_building(c.variant) {
+            g.components
+                .buildings
+                .entry(c.variant)
+                .or_default()
+                .insert(p);
+        }
+    }
+    hexgrid::set(p, c, &mut g.matrix);
+}
@@ -0,0 +1,54 @@
+use std::fmt;
+
+use crate::{
+    celldata::{CellState, CellStateVariant},
+    hexgrid::Pos,
+    resource::{self, ResourcePacket},
+};
+
+// Anything the game logic refuses to do.
+// None of these are bugs in the player's input, they are shown to the player instead of crashing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameError {
+    // no hub reaches pos
+    NotConnected(Pos),
+    // no hub in reach of pos could hand out the packet, negative amounts are taken, positive given
+    NotEnoughResources(Pos, ResourcePacket),
+    // the cell at pos can't hold any more of what was added
+    NoCapacity(Pos, CellState),
+    CannotBuild(CellStateVariant, Pos),
+    // the cell at pos is in a state the action can't work with
+    UnexpectedCell(Pos, CellState),
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameError::NotConnected(p) => write!(f, "{}, {} is not reached by any hub", p.x, p.y),
+            GameError::NotEnoughResources(p, packet) => {
+                let wanted: Vec<String> = enum_iterator::all::<resource::ResourceType>()
+                    .filter(|t| packet[*t as usize] != 0)
+                    .map(|t| format!("{} {:?}", packet[t as usize].abs(), t))
+                    .collect();
+                write!(
+                    f,
+                    "no hub in reach of {}, {} has {}",
+                    p.x,
+                    p.y,
+                    wanted.join(", ")
+                )
+            }
+            GameError::NoCapacity(p, c) => {
+                write!(f, "{} at {}, {} has no room left", c.variant, p.x, p.y)
+            }
+            GameError::CannotBuild(cv, p) => write!(f, "{} can't be built at {}, {}", cv, p.x, p.y),
+            GameError::UnexpectedCell(p, c) => {
+                write!(
+                    f,
+                    "unexpected {} at {}, {}: {:?}",
+                    c.variant, p.x, p.y, c.data
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use std::{fmt, fs, path::Path};
+
+use crate::{celldata::CellStateVariant, hexgrid::Pos, GameState};
+
+// Things that happened during play the player should hear about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameEvent {
+    BuildStarted(CellStateVariant, Pos),
+    BuildFinished(CellStateVariant, Pos),
+    // no hub in reach had the wood for a single build action this turn
+    BuildStalled(CellStateVariant, Pos),
+    // cv finished producing but no hub in reach had room for it
+    HubFull(CellStateVariant, Pos),
+    // the cell of a queued blueprint no longer offers its building
+    BlueprintDropped(CellStateVariant, Pos),
+    HotDone(Pos),
+    Sold(Pos),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoggedEvent {
+    pub turn: u32,
+    pub event: GameEvent,
+}
+
+// oldest first
+pub type EventLog = im::Vector<LoggedEvent>;
+
+pub fn new_log() -> EventLog {
+    im::Vector::new()
+}
+
+pub fn pos(e: GameEvent) -> Pos {
+    match e {
+        GameEvent::BuildStarted(_, p)
+        | GameEvent::BuildFinished(_, p)
+        | GameEvent::BuildStalled(_, p)
+        | GameEvent::HubFull(_, p)
+        | GameEvent::BlueprintDropped(_, p)
+        | GameEvent::HotDone(p)
+        | GameEvent::Sold(p) => p,
+    }
+}
+
+// kept free of commas so it can go into the csv as is
+impl fmt::Display for GameEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameEvent::BuildStarted(cv, _) => write!(f, "{} construction started", cv),
+            GameEvent::BuildFinished(cv, _) => write!(f, "{} finished", cv),
+            GameEvent::BuildStalled(cv, _) => write!(f, "{} stalled for lack of wood", cv),
+            GameEvent::HubFull(cv, _) => write!(f, "no hub has room for {} output", cv),
+            GameEvent::BlueprintDropped(cv, _) => write!(f, "{} blueprint dropped", cv),
+            GameEvent::HotDone(_) => write!(f, "Hot cell done"),
+            GameEvent::Sold(_) => write!(f, "item sold"),
+        }
+    }
+}
+
+pub fn push(e: GameEvent, g: &mut GameState) {
+    g.events.push_back(LoggedEvent {
+        turn: g.turn,
+        event: e,
+    });
+}
+
+pub type Stuck = im::HashSet<Pos>;
+
+// for events that would otherwise repeat every turn, only the first turn p is stuck is logged
+pub fn push_stuck(e: GameEvent, g: &mut GameState) {
+    if g.stuck.insert(pos(e)).is_none() {
+        push(e, g);
+    }
+}
+
+// p got going again, the next time it gets stuck is reported again
+pub fn clear_stuck(p: Pos, g: &mut GameState) {
+    g.stuck.remove(&p);
+}
+
+pub fn to_csv(log: &EventLog) -> String {
+    let mut lines = vec!["turn,x,y,event".to_string()];
+    for e in log {
+        let p = pos(e.event);
+        lines.push(format!("{},{},{},{}", e.turn, p.x, p.y, e.event));
+    }
+    lines.join("\n") + "\n"
+}
+
+pub fn write_csv(log: &EventLog, path: &Path) -> std::io::Result<()> {
+    fs::write(path, to_csv(log))
+}
@@ -0,0 +1,44 @@
+use std::fmt::Debug;
+
+use crate::hexgrid::XYCont;
+
+#[derive(Debug, Clone)]
+pub struct Matrix<T> {
+    size_y: usize,
+    data: Vec<T>,
+}
+
+pub fn new<T: Clone>(size_x: usize, size_y: usize, s: Vec<Vec<T>>) -> Matrix<T> {
+    let mut data = Vec::with_capacity(size_x * size_y);
+    // column after column, the same order get and set index in
+    for column in s.iter().take(size_x) {
+        for cell in column.iter().take(size_y) {
+            data.push(cell.clone())
+        }
+    }
+    Matrix { size_y, data }
+}
+
+pub fn get<T: Clone + std::cmp::PartialEq + Debug>(
+    m: &Matrix<T>,
+    XYCont { x, y }: XYCont<usize>,
+) -> Option<&T> {
+    let index = (x * m.size_y) + y;
+    m.data.get(index)
+}
+
+pub fn get_mut<T>(m: &mut Matrix<T>, XYCont { x, y }: XYCont<usize>) -> Option<&mut T> {
+    let index = (x * m.size_y) + y;
+    m.data.get_mut(index)
+}
+
+pub fn iter<T>(m: &Matrix<T>) -> impl Iterator<Item = (XYCont<usize>, &T)> {
+    let size_y = m.size_y;
+    m.data.iter().enumerate().map(move |(index, i)| {
+        let key = XYCont {
+            x: index / size_y,
+            y: index % size_y,
+        };
+        (key, i)
+    })
+}
@@ -0,0 +1,573 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    ops::{Add, Mul},
+    sync::Arc,
+};
+
+use crate::{
+    celldata,
+    make_world::{self},
+};
+use std::hash::Hash;
+
+pub mod matrix;
+#[cfg(test)]
+mod tests;
+
+pub const CHUNK_SIZE: usize = 0x100;
+const INDEX_MASK: i32 = CHUNK_SIZE as i32 - 1;
+const CHUNK_MASK: i32 = !INDEX_MASK;
+
+// Chunks are shared between clones of the grid, a chunk is only copied
+// the first time a clone writes to it. The map of chunks is persistent as well,
+// so cloning the grid doesn't depend on how much of it is loaded
+#[derive(Debug, Clone)]
+pub struct Hexgrid<T: CellGen<GenContext = C>, C: Clone> {
+    chunks: im::HashMap<XYCont<i32>, Arc<Chunk<T>>>,
+    gen_context: C,
+    out_of_bounds: T,
+}
+
+pub trait CellGen {
+    type GenContext;
+    fn new_chunk(p: Pos, c: &mut Self::GenContext) -> Chunk<Self>
+    where
+        Self: Sized;
+}
+
+#[derive(Debug, Clone)]
+pub enum EmptyContext {
+    None,
+}
+
+//Could be array if generalized array initalization was easy
+pub type Chunk<T> = matrix::Matrix<T>;
+
+pub type Board = Hexgrid<celldata::CellState, make_world::GenContext>;
+pub type Pos = XYCont<i32>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct XYCont<C> {
+    pub x: C,
+    pub y: C,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct XYZCont<C> {
+    x: C,
+    y: C,
+    z: C,
+}
+
+impl Mul<XYCont<i32>> for XYCont<i32> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        XYCont {
+            x: self.x * rhs.x,
+            y: self.y * rhs.y,
+        }
+    }
+}
+
+impl Mul<i32> for XYCont<i32> {
+    type Output = Self;
+    fn mul(self, rhs: i32) -> Self {
+        XYCont {
+            x: self.x * rhs,
+            y: self.y * rhs,
+        }
+    }
+}
+
+impl Mul<XYCont<i32>> for i32 {
+    type Output = XYCont<i32>;
+    fn mul(self, rhs: XYCont<i32>) -> Self::Output {
+        XYCont {
+            x: rhs.x * self,
+            y: rhs.y * self,
+        }
+    }
+}
+
+impl Add<XYCont<i32>> for XYCont<i32> {
+    type Output = XYCont<i32>;
+    fn add(self, rhs: XYCont<i32>) -> Self::Output {
+        XYCont {
+            x: rhs.x + self.x,
+            y: rhs.y + self.y,
+        }
+    }
+}
+
+pub fn new<T: Clone + CellGen<GenContext = C>, C: Clone>(
+    gen_context: C,
+    out_of_bounds: T,
+) -> Hexgrid<T, C> {
+    Hexgrid {
+        chunks: im::HashMap::new(),
+        gen_context,
+        out_of_bounds,
+    }
+}
+
+pub fn chunk_from_example<T: Clone + CellGen<GenContext = C>, C: Clone>(example: T) -> Chunk<T> {
+    let mut ret = vec![];
+    for _ in 0..CHUNK_SIZE {
+        let mut row = vec![];
+        for _ in 0..CHUNK_SIZE {
+            row.push(example.clone())
+        }
+        ret.push(row)
+    }
+    matrix::new(CHUNK_SIZE, CHUNK_SIZE, ret)
+}
+
+// keys (top left positions) of every chunk generated so far
+pub fn loaded_chunks<T: CellGen<GenContext = C>, C: Clone>(
+    source: &Hexgrid<T, C>,
+) -> impl Iterator<Item = Pos> + '_ {
+    source.chunks.keys().cloned()
+}
+
+// every cell of every chunk generated so far, in no particular order
+pub fn loaded_cells<T: CellGen<GenContext = C>, C: Clone>(
+    source: &Hexgrid<T, C>,
+) -> impl Iterator<Item = (Pos, &T)> + '_ {
+    source.chunks.iter().flat_map(|(chunk_key, chunk)| {
+        matrix::iter(chunk).map(move |(XYCont { x, y }, i)| {
+            let p = *chunk_key
+                + XYCont {
+                    x: x as i32,
+                    y: y as i32,
+                };
+            (p, i)
+        })
+    })
+}
+
+pub fn touch_all_chunks<
+    T: Clone + CellGen<GenContext = C> + std::cmp::PartialEq + std::fmt::Debug,
+    C: Clone,
+>(
+    source: &mut Hexgrid<T, C>,
+    XYCont { x: x0, y: y0 }: XYCont<i32>,
+    height_extra: i32,
+    width_extra: i32,
+) {
+    for dx in 0..(height_extra + 1) {
+        for dy in 0..(width_extra + 1) {
+            let c = XYCont {
+                x: x0 + dx,
+                y: y0 + dy,
+            };
+            if is_chunk_corner(c) {
+                get(c, source);
+            }
+        }
+    }
+}
+
+fn is_chunk_corner(XYCont { x: x0, y: y0 }: XYCont<i32>) -> bool {
+    let x = x0 & INDEX_MASK;
+    let y = y0 & INDEX_MASK;
+    ((x == 0) || (x == INDEX_MASK)) && (y == 0 || (y == INDEX_MASK))
+}
+
+pub struct PortIterator<'a, T: CellGen<GenContext = C>, C: Clone> {
+    x: i32,
+    x_max: i32,
+    y_min: i32,
+    y_max: i32,
+    source: &'a Hexgrid<T, C>,
+}
+
+pub struct RowIterator<'a, T: CellGen<GenContext = C>, C: Clone> {
+    x: i32,
+    y: i32,
+    y_max: i32,
+    source: &'a Hexgrid<T, C>,
+}
+
+impl<'a, T: CellGen<GenContext = C>, C: Clone> Iterator for PortIterator<'a, T, C> {
+    type Item = (i32, RowIterator<'a, T, C>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.x < self.x_max {
+            let ret = Some((
+                self.x,
+                RowIterator {
+                    x: self.x,
+                    y_max: self.y_max,
+                    y: self.y_min,
+                    source: self.source,
+                },
+            ));
+            self.x += 1;
+            ret
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: CellGen<GenContext = C> + Clone + std::cmp::PartialEq + std::fmt::Debug, C: Clone> Iterator
+    for RowIterator<'_, T, C>
+{
+    type Item = (XYCont<i32>, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.y < self.y_max {
+            let p = XYCont {
+                x: self.x,
+                y: self.y,
+            };
+            let ret = Some((p, unsafe_get(p, self.source)));
+            self.y += 1;
+            ret
+        } else {
+            None
+        }
+    }
+}
+
+pub fn view_port<
+    'a,
+    T: Clone + CellGen<GenContext = C> + std::cmp::PartialEq + std::fmt::Debug + Sized,
+    C: Clone,
+>(
+    source: &'a Hexgrid<T, C>,
+    XYCont { x, y }: XYCont<i32>,
+    height_extra: i32,
+    width_extra: i32,
+) -> PortIterator<'a, T, C> {
+    PortIterator {
+        x,
+        y_min: y,
+        x_max: x + height_extra,
+        y_max: y + width_extra,
+        source,
+    }
+}
+
+pub fn pos_iter_to_cells<
+    'a,
+    T: Clone + CellGen<GenContext = C> + std::cmp::PartialEq + std::fmt::Debug,
+    C: Clone,
+>(
+    pos: impl IntoIterator<Item = Pos> + 'a,
+    m: &'a mut Hexgrid<T, C>,
+) -> impl Iterator<Item = (Pos, T)> + 'a {
+    pos.into_iter().map(|p| (p, get(p, m)))
+}
+
+// Breadth first over the cells t holds for, starting from p and its neighbours,
+// so p doesn't have to be part of what is searched for.
+// Every cell is visited once and the result is in order of discovery.
+// Stops right after the first cell stop holds for and doesn't leave max_radius around p
+pub fn flood<
+    T: Clone + CellGen<GenContext = C> + std::cmp::PartialEq + std::fmt::Debug,
+    C: Clone,
+>(
+    p: Pos,
+    t: impl Fn(&T) -> bool,
+    stop: impl Fn(&T) -> bool,
+    max_radius: Option<i32>,
+    m: &mut Hexgrid<T, C>,
+) -> Vec<(Pos, T)> {
+    let in_range = |i: Pos| match max_radius {
+        Some(r) => distance(p, i) <= r,
+        None => true,
+    };
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::new();
+    let mut ret = vec![];
+    for i in [p]
+        .into_iter()
+        .chain(neighbor_pos(p))
+        .filter(|i| in_range(*i))
+    {
+        visited.insert(i);
+        queue.push_back(i);
+    }
+    while let Some(i) = queue.pop_front() {
+        let c = get_ref(i, m);
+        if !t(c) {
+            continue;
+        }
+        let is_done = stop(c);
+        ret.push((i, c.clone()));
+        if is_done {
+            break;
+        }
+        for n in neighbor_pos(i) {
+            if in_range(n) && visited.insert(n) {
+                queue.push_back(n);
+            }
+        }
+    }
+    ret
+}
+
+const CUBE_DIRECTIONS: [(i32, i32); 6] = [(1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1), (0, 1)];
+
+fn cube_add(
+    XYZCont { x: q, y: r, .. }: XYZCont<i32>,
+    (dq, dr): (i32, i32),
+    n: i32,
+) -> XYZCont<i32> {
+    let q1 = q + dq * n;
+    let r1 = r + dr * n;
+    XYZCont {
+        x: q1,
+        y: r1,
+        z: -q1 - r1,
+    }
+}
+
+// the six cells around p, always in the same order
+pub fn neighbor_pos(p: Pos) -> [Pos; 6] {
+    let c = xy_to_cube(p);
+    CUBE_DIRECTIONS.map(|d| cube_to_xy(cube_add(c, d, 1)))
+}
+
+// Every cell at exactly distance radius from center, going around once.
+// Starts below left of center and always visits in the same order, radius 0 is just center
+pub struct Ring {
+    current: XYZCont<i32>,
+    radius: i32,
+    side: usize,
+    step: i32,
+}
+
+pub fn ring(center: Pos, radius: i32) -> Ring {
+    Ring {
+        current: cube_add(xy_to_cube(center), CUBE_DIRECTIONS[4], radius),
+        radius,
+        side: 0,
+        step: 0,
+    }
+}
+
+impl Iterator for Ring {
+    type Item = Pos;
+
+    fn next(&mut self) -> Option<Pos> {
+        if self.side >= CUBE_DIRECTIONS.len() || self.radius < 0 {
+            return None;
+        }
+        let ret = cube_to_xy(self.current);
+        if self.radius == 0 {
+            self.side = CUBE_DIRECTIONS.len();
+            return Some(ret);
+        }
+        self.current = cube_add(self.current, CUBE_DIRECTIONS[self.side], 1);
+        self.step += 1;
+        if self.step == self.radius {
+            self.step = 0;
+            self.side += 1;
+        }
+        Some(ret)
+    }
+}
+
+// every cell within radius of center, ring by ring from the center out
+pub fn spiral(center: Pos, radius: i32) -> impl Iterator<Item = Pos> {
+    (0..=radius).flat_map(move |r| ring(center, r))
+}
+
+pub fn within<
+    'a,
+    T: Clone + CellGen<GenContext = C> + std::cmp::PartialEq + std::fmt::Debug,
+    C: Clone,
+>(
+    p: Pos,
+    m: &'a mut Hexgrid<T, C>,
+    range: i32,
+) -> impl Iterator<Item = (Pos, T)> + 'a {
+    pos_iter_to_cells(spiral(p, range), m)
+}
+
+// p itself and the six cells around it
+pub fn neighbors<
+    'a,
+    T: Clone + CellGen<GenContext = C> + std::cmp::PartialEq + std::fmt::Debug,
+    C: Clone,
+>(
+    p: Pos,
+    m: &'a mut Hexgrid<T, C>,
+) -> impl Iterator<Item = (Pos, T)> + 'a {
+    within(p, m, 1)
+}
+
+// generates the chunk if it doesn't exist yet
+fn load_chunk<T: CellGen<GenContext = C>, C: Clone>(
+    chunk_key: Pos,
+    m: &mut Hexgrid<T, C>,
+) -> &mut Arc<Chunk<T>> {
+    let gen_context = &mut m.gen_context;
+    m.chunks
+        .entry(chunk_key)
+        .or_insert_with(|| Arc::new(T::new_chunk(chunk_key, gen_context)))
+}
+
+pub fn get_ref<
+    T: Clone + CellGen<GenContext = C> + std::cmp::PartialEq + std::fmt::Debug,
+    C: Clone,
+>(
+    p: Pos,
+    m: &mut Hexgrid<T, C>,
+) -> &T {
+    let (chunk_key, in_chunk_key) = to_chunk_keys(p);
+    matrix::get(load_chunk(chunk_key, m), in_chunk_key).unwrap()
+}
+
+// copies the chunk first if another clone of the grid still shares it
+pub fn get_mut<
+    T: Clone + CellGen<GenContext = C> + std::cmp::PartialEq + std::fmt::Debug,
+    C: Clone,
+>(
+    p: Pos,
+    m: &mut Hexgrid<T, C>,
+) -> &mut T {
+    let (chunk_key, in_chunk_key) = to_chunk_keys(p);
+    matrix::get_mut(Arc::make_mut(load_chunk(chunk_key, m)), in_chunk_key).unwrap()
+}
+
+pub fn set<T: Clone + CellGen<GenContext = C> + std::cmp::PartialEq + std::fmt::Debug, C: Clone>(
+    p: Pos,
+    new_cell: T,
+    m: &mut Hexgrid<T, C>,
+) {
+    *get_mut(p, m) = new_cell;
+}
+
+pub fn get<T: Clone + CellGen<GenContext = C> + std::cmp::PartialEq + std::fmt::Debug, C: Clone>(
+    p: Pos,
+    m: &mut Hexgrid<T, C>,
+) -> T {
+    get_ref(p, m).clone()
+}
+
+pub fn unsafe_get<
+    T: Clone + CellGen<GenContext = C> + std::cmp::PartialEq + std::fmt::Debug,
+    C: Clone,
+>(
+    p: Pos,
+    m: &Hexgrid<T, C>,
+) -> T {
+    let (chunk_key, in_chunk_key) = to_chunk_keys(p);
+    if let Some(chunk) = m.chunks.get(&chunk_key) {
+        matrix::get(chunk, in_chunk_key).unwrap().clone()
+    } else {
+        m.out_of_bounds.clone()
+    }
+}
+
+pub fn to_chunk_keys(Pos { x, y }: Pos) -> (XYCont<i32>, XYCont<usize>) {
+    let chunk_key = XYCont {
+        x: x & CHUNK_MASK,
+        y: y & CHUNK_MASK,
+    };
+    let in_chunk_key = XYCont {
+        x: (x & INDEX_MASK) as usize,
+        y: (y & INDEX_MASK) as usize,
+    };
+    (chunk_key, in_chunk_key)
+}
+
+// https://www.redblobgames.com/grids/hexagons/
+pub fn distance<C: TryInto<i32>>(from: XYCont<C>, to: XYCont<C>) -> i32
+where
+    <C as TryInto<i32>>::Error: std::fmt::Debug,
+{
+    let from_q = xy_to_cube(from);
+    let to_q = xy_to_cube(to);
+    cube_distance(from_q, to_q) / 2
+}
+
+// every hex on the straight line from `from` to `to`, both included, in order
+pub fn line(from: Pos, to: Pos) -> impl Iterator<Item = Pos> {
+    let n = distance(from, to);
+    let a = xy_to_cube(from);
+    let b = xy_to_cube(to);
+    // nudged off the edges between hexes, so points exactly between two round the same way
+    let (a_q, a_r) = (a.x as f32 + 1e-6, a.y as f32 + 1e-6);
+    let (b_q, b_r) = (b.x as f32 + 1e-6, b.y as f32 + 1e-6);
+    (0..=n).map(move |i| {
+        let t = if n == 0 { 0.0 } else { i as f32 / n as f32 };
+        cube_to_xy(cube_round(lerp(a_q, b_q, t), lerp(a_r, b_r, t)))
+    })
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+fn cube_distance(from: XYZCont<i32>, to: XYZCont<i32>) -> i32 {
+    (from.x - to.x).abs() + (from.y - to.y).abs() + (from.z - to.z).abs()
+}
+
+fn cube_to_xy(XYZCont { x: q, y: r, .. }: XYZCont<i32>) -> Pos {
+    XYCont {
+        x: q,
+        y: r + (q + (q & 1)) / 2,
+    }
+}
+
+// rounds fractional cube coordinates to the hex they fall in
+fn cube_round(q: f32, r: f32) -> XYZCont<i32> {
+    let s = -q - r;
+    let mut rq = q.round();
+    let mut rr = r.round();
+    let rs = s.round();
+    let dq = (rq - q).abs();
+    let dr = (rr - r).abs();
+    let ds = (rs - s).abs();
+    if dq > dr && dq > ds {
+        rq = -rr - rs;
+    } else if dr > ds {
+        rr = -rq - rs;
+    }
+    XYZCont {
+        x: rq as i32,
+        y: rr as i32,
+        z: -(rq as i32) - (rr as i32),
+    }
+}
+
+// Pixel position of the center of a hex, hexes are flat topped
+// and `size` is the distance from center to corner.
+// Even columns sit half a hex lower than odd ones, matching xy_to_cube
+pub fn to_pixel(p: Pos, size: f32) -> (f32, f32) {
+    let XYZCont { x: q, y: r, .. } = xy_to_cube(p);
+    let x = size * 1.5 * q as f32;
+    let y = size * 3.0_f32.sqrt() * (r as f32 + q as f32 / 2.0);
+    (x, y)
+}
+
+// inverse of to_pixel, any point inside a hex maps to that hex
+pub fn from_pixel((x, y): (f32, f32), size: f32) -> Pos {
+    let q = (2.0 / 3.0 * x) / size;
+    let r = (-1.0 / 3.0 * x + 3.0_f32.sqrt() / 3.0 * y) / size;
+    cube_to_xy(cube_round(q, r))
+}
+
+fn xy_to_cube<C: TryInto<i32>>(
+    XYCont {
+        x: from_x,
+        y: from_y,
+    }: XYCont<C>,
+) -> XYZCont<i32>
+where
+    <C as TryInto<i32>>::Error: std::fmt::Debug,
+{
+    let x = from_x.try_into().unwrap();
+    let y = from_y.try_into().unwrap();
+    let q = x;
+    let r = y - (x + (x & 1)) / 2;
+    XYZCont {
+        x: q,
+        y: r,
+        z: (-q - r),
+    }
+}
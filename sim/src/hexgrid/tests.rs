@@ -0,0 +1,208 @@
+use std::collections::HashSet;
+
+use proptest::prelude::*;
+
+use super::*;
+
+fn pos() -> impl Strategy<Value = Pos> {
+    (-1000..1000, -1000..1000).prop_map(|(x, y)| XYCont { x, y })
+}
+
+// within a couple of cells of where chunks meet, in every quadrant
+fn seam_pos() -> impl Strategy<Value = Pos> {
+    let chunk = CHUNK_SIZE as i32;
+    let near_seam = (-4..4, -2..2).prop_map(move |(k, d)| k * chunk + d);
+    (near_seam.clone(), near_seam).prop_map(|(x, y)| XYCont { x, y })
+}
+
+fn any_pos() -> impl Strategy<Value = Pos> {
+    prop_oneof![pos(), seam_pos()]
+}
+
+// every cell within radius, found the slow way
+fn brute_within(center: Pos, radius: i32) -> HashSet<Pos> {
+    let mut ret = HashSet::new();
+    for x in center.x - radius..=center.x + radius {
+        for y in center.y - 2 * radius..=center.y + 2 * radius {
+            let p = XYCont { x, y };
+            if distance(center, p) <= radius {
+                ret.insert(p);
+            }
+        }
+    }
+    ret
+}
+
+#[test]
+fn chunk_keys_at_the_seams() {
+    let chunk = CHUNK_SIZE as i32;
+    let last = CHUNK_SIZE - 1;
+    let cases = [
+        ((0, 0), (0, 0), (0, 0)),
+        ((-1, -1), (-chunk, -chunk), (last, last)),
+        ((-1, 0), (-chunk, 0), (last, 0)),
+        ((0, -1), (0, -chunk), (0, last)),
+        ((chunk - 1, chunk), (0, chunk), (last, 0)),
+        ((-chunk, -chunk - 1), (-chunk, -2 * chunk), (0, last)),
+    ];
+    for ((x, y), (cx, cy), (ix, iy)) in cases {
+        assert_eq!(
+            to_chunk_keys(XYCont { x, y }),
+            (XYCont { x: cx, y: cy }, XYCont { x: ix, y: iy }),
+            "{}, {}",
+            x,
+            y
+        );
+    }
+}
+
+#[test]
+fn chunk_corners_at_the_seams() {
+    let chunk = CHUNK_SIZE as i32;
+    for (x, y) in [(0, 0), (-1, -1), (-1, 0), (chunk - 1, -chunk), (chunk, -1)] {
+        assert!(is_chunk_corner(XYCont { x, y }), "{}, {}", x, y);
+    }
+    for (x, y) in [(1, 0), (0, -2), (-chunk / 2, 0), (chunk + 1, chunk - 1)] {
+        assert!(!is_chunk_corner(XYCont { x, y }), "{}, {}", x, y);
+    }
+}
+
+// odd columns sit half a hex higher on screen than even ones, also left of the origin
+#[test]
+fn cube_coordinates_of_odd_and_even_columns() {
+    let cases = [
+        ((0, 0), (0, 0)),
+        ((1, 0), (1, -1)),
+        ((1, 1), (1, 0)),
+        ((-1, 0), (-1, 0)),
+        ((-1, -1), (-1, -1)),
+        ((-2, 0), (-2, 1)),
+        ((-3, 2), (-3, 3)),
+    ];
+    for ((x, y), (q, r)) in cases {
+        assert_eq!(
+            xy_to_cube(XYCont { x, y }),
+            XYZCont {
+                x: q,
+                y: r,
+                z: -q - r
+            },
+            "{}, {}",
+            x,
+            y
+        );
+    }
+}
+
+proptest! {
+    #[test]
+    fn chunk_keys_round_trip(p in any_pos()) {
+        let (chunk, in_chunk) = to_chunk_keys(p);
+        let size = CHUNK_SIZE as i32;
+        prop_assert!(in_chunk.x < CHUNK_SIZE && in_chunk.y < CHUNK_SIZE);
+        prop_assert_eq!(chunk.x.rem_euclid(size), 0);
+        prop_assert_eq!(chunk.y.rem_euclid(size), 0);
+        prop_assert_eq!(chunk.x + in_chunk.x as i32, p.x);
+        prop_assert_eq!(chunk.y + in_chunk.y as i32, p.y);
+        // the chunk key is where the chunk starts, so it is in its own chunk at 0, 0
+        prop_assert_eq!(to_chunk_keys(chunk), (chunk, XYCont { x: 0, y: 0 }));
+    }
+
+    #[test]
+    fn chunk_corners_are_the_chunks_four_corners(p in any_pos()) {
+        let (_, XYCont { x, y }) = to_chunk_keys(p);
+        let edge = |i: usize| i == 0 || i == CHUNK_SIZE - 1;
+        prop_assert_eq!(is_chunk_corner(p), edge(x) && edge(y));
+    }
+
+    #[test]
+    fn cube_coordinates_round_trip(p in any_pos()) {
+        let c = xy_to_cube(p);
+        prop_assert_eq!(c.x + c.y + c.z, 0);
+        prop_assert_eq!(cube_to_xy(c), p);
+    }
+
+    #[test]
+    fn distance_is_a_metric(a in any_pos(), b in any_pos(), c in any_pos()) {
+        prop_assert_eq!(distance(a, b), distance(b, a));
+        prop_assert_eq!(distance(a, b) == 0, a == b);
+        prop_assert!(distance(a, c) <= distance(a, b) + distance(b, c));
+    }
+
+    // shifting by an odd number of columns changes which columns are the low ones
+    #[test]
+    fn distance_is_unchanged_by_even_column_shifts(
+        a in any_pos(),
+        b in any_pos(),
+        (dx, dy) in (-500..500, -500..500),
+    ) {
+        let shift = |p: Pos| XYCont { x: p.x + 2 * dx, y: p.y + dy };
+        prop_assert_eq!(distance(shift(a), shift(b)), distance(a, b));
+    }
+
+    #[test]
+    fn distance_is_the_same_for_usize(a in 0..2000usize, b in 0..2000usize, c in 0..2000usize, d in 0..2000usize) {
+        let wide = distance(
+            XYCont { x: a as i32, y: b as i32 },
+            XYCont { x: c as i32, y: d as i32 },
+        );
+        prop_assert_eq!(distance(XYCont { x: a, y: b }, XYCont { x: c, y: d }), wide);
+    }
+
+    #[test]
+    fn neighbors_across_seams_are_at_distance_one(p in seam_pos()) {
+        for n in neighbor_pos(p) {
+            prop_assert_eq!(distance(p, n), 1);
+        }
+    }
+
+    #[test]
+    fn neighbors_are_the_cells_at_distance_one(p in pos()) {
+        let n: HashSet<Pos> = neighbor_pos(p).into_iter().collect();
+        prop_assert_eq!(n.len(), 6);
+        let mut expected = brute_within(p, 1);
+        expected.remove(&p);
+        prop_assert_eq!(n, expected);
+    }
+
+    #[test]
+    fn ring_is_every_cell_at_distance_r_once(center in pos(), r in 0..20) {
+        let cells: Vec<Pos> = ring(center, r).collect();
+        prop_assert_eq!(cells.len(), if r == 0 { 1 } else { 6 * r as usize });
+        let unique: HashSet<Pos> = cells.iter().cloned().collect();
+        prop_assert_eq!(unique.len(), cells.len());
+        for p in &cells {
+            prop_assert_eq!(distance(center, *p), r);
+        }
+        // going around, every step is to a neighbour, including back to the start
+        if r > 0 {
+            for (a, b) in cells.iter().zip(cells.iter().cycle().skip(1)) {
+                prop_assert_eq!(distance(*a, *b), 1);
+            }
+        }
+    }
+
+    #[test]
+    fn spiral_is_within_ring_by_ring(center in pos(), r in 0..12) {
+        let cells: Vec<Pos> = spiral(center, r).collect();
+        prop_assert_eq!(cells.len(), 1 + 3 * (r * (r + 1)) as usize);
+        let unique: HashSet<Pos> = cells.iter().cloned().collect();
+        prop_assert_eq!(&unique, &brute_within(center, r));
+        let distances: Vec<i32> = cells.iter().map(|p| distance(center, *p)).collect();
+        let mut sorted = distances.clone();
+        sorted.sort();
+        prop_assert_eq!(distances, sorted);
+    }
+
+    #[test]
+    fn line_steps_one_cell_at_a_time(from in pos(), (dx, dy) in (-40..40, -40..40)) {
+        let to = XYCont { x: from.x + dx, y: from.y + dy };
+        let cells: Vec<Pos> = line(from, to).collect();
+        prop_assert_eq!(cells.len() as i32, distance(from, to) + 1);
+        prop_assert_eq!(cells[0], from);
+        prop_assert_eq!(*cells.last().unwrap(), to);
+        for (i, p) in cells.iter().enumerate() {
+            prop_assert_eq!(distance(from, *p), i as i32);
+        }
+    }
+}
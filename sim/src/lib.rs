@@ -0,0 +1,80 @@
+// The game itself, without any UI: the board, what is built on it and how turns play out.
+// Everything goes through GameState, which is cheap to clone,
+// so a UI can keep the last state around while working on the next
+pub mod actionmachine;
+pub mod build_queue;
+pub mod building;
+pub mod celldata;
+pub mod components;
+pub mod error;
+pub mod events;
+pub mod hexgrid;
+pub mod logistics_plane;
+pub mod make_world;
+pub mod menu;
+pub mod resource;
+pub mod stats;
+pub mod validate;
+
+#[derive(Clone)]
+pub struct GameState {
+    pub matrix: hexgrid::Board,
+    pub logistics_plane: logistics_plane::LogisticsPlane,
+    pub resources: GameResources,
+    pub action_machine: actionmachine::ActionMachine,
+    // number of turns run so far
+    pub turn: u32,
+    pub stats: stats::StatsLog,
+    pub build_queue: build_queue::BuildQueue,
+    pub events: events::EventLog,
+    // cells whose stall or full hub was already reported and hasn't cleared since
+    pub stuck: events::Stuck,
+    // kept up to date by components::set, cells changing variant must go through it
+    pub components: components::ComponentIndex,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct GameResources {
+    pub tiles: i32,
+    pub leak: i32,
+    pub heat_efficency: f64,
+}
+
+// leak of a game without any Hot cells
+pub const START_LEAK: i32 = 1;
+
+// Generates the board under the width x height cells from top_left, for whoever is about to show them.
+// Only chunks are added, the game itself doesn't change
+pub fn ensure_loaded(top_left: hexgrid::Pos, width: i32, height: i32, g: &mut GameState) {
+    hexgrid::touch_all_chunks(&mut g.matrix, top_left, width - 1, height - 1);
+}
+
+// where the starting hub is built
+pub const START_POS: hexgrid::Pos = hexgrid::XYCont { x: 0, y: 0 };
+
+pub fn new_game(matrix: hexgrid::Board) -> GameState {
+    let mut g = GameState {
+        matrix,
+        logistics_plane: logistics_plane::new_plane(),
+        resources: GameResources {
+            tiles: 0,
+            leak: START_LEAK,
+            heat_efficency: 0.0,
+        },
+        action_machine: actionmachine::new(),
+        turn: 0,
+        stats: stats::new_log(),
+        build_queue: build_queue::new(),
+        events: events::new_log(),
+        stuck: im::HashSet::new(),
+        components: components::new(),
+    };
+    let p = START_POS;
+    let cv = celldata::CellStateVariant::Hub;
+    g = building::do_build(actionmachine::Other::CellStateVariant(cv), p, g)
+        .expect("the starting hub can always be built");
+    let mut start_hub = hexgrid::get(p, &mut g.matrix);
+    start_hub = resource::add(resource::ResourceType::Wood, start_hub, 10).unwrap();
+    hexgrid::set(p, start_hub, &mut g.matrix);
+    stats::record(g)
+}
@@ -3,6 +3,8 @@ use std::hash::Hash;
 
 use crate::{
     celldata::{CellState, CellStateData, CellStateVariant},
+    components,
+    error::GameError,
     hexgrid::{self, Pos},
     resource, GameState,
 };
@@ -10,17 +12,17 @@ use crate::{
 // mirror of the main board (hexgrid::Board) in size
 // for use of the building subsytem
 // need to keep "available logistics" somewhere
-pub(crate) type LogisticsPlane = hexgrid::Hexgrid<LogisticsState, hexgrid::EmptyContext>;
+pub type LogisticsPlane = hexgrid::Hexgrid<LogisticsState, hexgrid::EmptyContext>;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub(crate) struct Available {
-    pub(crate) locations: HashSet<hexgrid::Pos>,
-    pub(crate) borrows: HashMap<Pos, resource::ResourcePacket>,
-    pub(crate) taken_lp: HashMap<Pos, resource::ResourceValue>,
+pub struct Available {
+    pub locations: HashSet<hexgrid::Pos>,
+    pub borrows: HashMap<Pos, resource::ResourcePacket>,
+    pub taken_lp: HashMap<Pos, resource::ResourceValue>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub(crate) enum LogisticsState {
+pub enum LogisticsState {
     None,
     Source,
     Available(Available),
@@ -34,11 +36,11 @@ impl hexgrid::CellGen for LogisticsState {
     }
 }
 
-pub(crate) fn new_plane() -> LogisticsPlane {
+pub fn new_plane() -> LogisticsPlane {
     hexgrid::new(hexgrid::EmptyContext::None, LogisticsState::None)
 }
 
-pub(crate) fn has_worker(pos: hexgrid::Pos, g: &GameState) -> bool {
+pub fn has_worker(pos: hexgrid::Pos, g: &GameState) -> bool {
     connected_sources(pos, g)
         .into_iter()
         .any(|i| can_use(pos, i, hexgrid::unsafe_get(i, &g.matrix)))
@@ -54,15 +56,14 @@ fn can_use(user: Pos, target: Pos, c: CellState) -> bool {
     }
 }
 
-pub(crate) fn return_borrows(pos: hexgrid::Pos, mut g: GameState) -> GameState {
-    let a = get_available(pos, &mut g);
-    g = a.borrows.iter().fold(g, |mut acc: GameState, (p, b)| {
-        let c0 = hexgrid::get(*p, &mut acc.matrix);
+pub fn return_borrows(pos: hexgrid::Pos, mut g: GameState) -> Result<GameState, GameError> {
+    let a = get_available(pos, &mut g)?;
+    for (p, b) in a.borrows.iter() {
+        let c0 = hexgrid::get(*p, &mut g.matrix);
         let b1 = resource::neg_packet(*b);
-        let c1 = resource::add_packet(b1, c0).unwrap();
-        hexgrid::set(*p, c1, &mut acc.matrix);
-        acc
-    });
+        let c1 = resource::add_packet(b1, c0).ok_or(GameError::NoCapacity(*p, c0))?;
+        hexgrid::set(*p, c1, &mut g.matrix);
+    }
     hexgrid::set(
         pos,
         LogisticsState::Available(Available {
@@ -71,17 +72,17 @@ pub(crate) fn return_borrows(pos: hexgrid::Pos, mut g: GameState) -> GameState {
         }),
         &mut g.logistics_plane,
     );
-    g
+    Ok(g)
 }
 
-pub(crate) fn return_lp(pos: hexgrid::Pos, mut g: GameState) -> GameState {
-    let a = get_available(pos, &mut g);
-    g = a.taken_lp.iter().fold(g, |mut acc: GameState, (p, b)| {
-        let c0 = hexgrid::get(*p, &mut acc.matrix);
-        let c1 = resource::add(resource::ResourceType::LogisticsPoints, c0, *b).unwrap();
-        hexgrid::set(*p, c1, &mut acc.matrix);
-        acc
-    });
+pub fn return_lp(pos: hexgrid::Pos, mut g: GameState) -> Result<GameState, GameError> {
+    let a = get_available(pos, &mut g)?;
+    for (p, b) in a.taken_lp.iter() {
+        let c0 = hexgrid::get(*p, &mut g.matrix);
+        let c1 = resource::add(resource::ResourceType::LogisticsPoints, c0, *b)
+            .ok_or(GameError::NoCapacity(*p, c0))?;
+        hexgrid::set(*p, c1, &mut g.matrix);
+    }
     hexgrid::set(
         pos,
         LogisticsState::Available(Available {
@@ -90,32 +91,29 @@ pub(crate) fn return_lp(pos: hexgrid::Pos, mut g: GameState) -> GameState {
         }),
         &mut g.logistics_plane,
     );
-    g
+    Ok(g)
 }
 
-pub(crate) fn try_take_resources(
+pub fn try_take_resources(
     src: Pos,
     p: resource::ResourcePacket,
     g: &mut GameState,
-) -> Option<GameState> {
-    dbg!(p);
+) -> Result<GameState, GameError> {
     try_resources(src, p, false, g)
 }
 
-pub(crate) fn try_borrow_resources(
+pub fn try_borrow_resources(
     src: Pos,
     p: resource::ResourcePacket,
     g: &mut GameState,
-) -> Option<GameState> {
+) -> Result<GameState, GameError> {
     try_resources(src, p, true, g)
 }
 
-fn get_available(src: Pos, g: &mut GameState) -> Available {
+fn get_available(src: Pos, g: &mut GameState) -> Result<Available, GameError> {
     match hexgrid::get(src, &mut g.logistics_plane) {
-        LogisticsState::Available(a) => a,
-        a => {
-            unimplemented!("{:?}", a)
-        }
+        LogisticsState::Available(a) => Ok(a),
+        _ => Err(GameError::NotConnected(src)),
     }
 }
 
@@ -124,20 +122,24 @@ fn try_resources(
     mut p: resource::ResourcePacket,
     is_borrow: bool,
     g: &mut GameState,
-) -> Option<GameState> {
+) -> Result<GameState, GameError> {
     p = resource::neg_packet(p);
-    let a = get_available(src, g);
+    let a = get_available(src, g)?;
     let mut vec: Vec<_> = a
         .locations
         .clone()
         .into_iter()
         .map(|i| (hexgrid::distance(src, i), i))
         .collect();
-    vec.sort_by(|(a, _), (b, _)| a.cmp(b));
+    vec.sort_by_key(|(a, _)| *a);
+    // when only handing resources to the hubs, fails because every hub is full are told apart
+    let is_give = p.iter().all(|v| *v >= 0);
+    let mut full = None;
+    let mut all_full = is_give && !vec.is_empty();
     for (distance, target) in vec {
         let p1 = resource::add_to_packet(resource::ResourceType::LogisticsPoints, -distance, p);
         let target_cell = hexgrid::get(target, &mut g.matrix);
-        dbg!(distance, target, p1);
+        tracing::trace!(?src, ?target, distance, ?p1, "try resources");
         if let Some(new) = resource::add_packet(p1, target_cell) {
             hexgrid::set(target, new, &mut g.matrix);
             if is_borrow {
@@ -147,10 +149,18 @@ fn try_resources(
                 let lp1 = update_take(a, distance, target);
                 hexgrid::set(src, lp1, &mut g.logistics_plane);
             }
-            return Some(g.clone());
+            return Ok(g.clone());
+        }
+        if is_give && resource::add_packet(p, target_cell).is_none() {
+            full = Some(GameError::NoCapacity(target, target_cell));
+        } else {
+            all_full = false;
         }
     }
-    None
+    match full {
+        Some(e) if all_full => Err(e),
+        _ => Err(GameError::NotEnoughResources(src, p)),
+    }
 }
 
 fn update_take(a: Available, p: resource::ResourceValue, target: Pos) -> LogisticsState {
@@ -159,12 +169,7 @@ fn update_take(a: Available, p: resource::ResourceValue, target: Pos) -> Logisti
 }
 
 fn update_borrows(a: Available, p: resource::ResourcePacket, target: Pos) -> LogisticsState {
-    let borrows = insert_or_join(
-        a.borrows,
-        |a, b| resource::add_packet_to_packet(a, b),
-        target,
-        p,
-    );
+    let borrows = insert_or_join(a.borrows, resource::add_packet_to_packet, target, p);
     LogisticsState::Available(Available { borrows, ..a })
 }
 
@@ -189,7 +194,8 @@ where
     m
 }
 
-fn connected_sources(pos: hexgrid::Pos, g: &GameState) -> Vec<hexgrid::Pos> {
+// hubs whose workers and stockpiles can be used at pos
+pub fn connected_sources(pos: hexgrid::Pos, g: &GameState) -> Vec<hexgrid::Pos> {
     match hexgrid::unsafe_get(pos, &g.logistics_plane) {
         LogisticsState::None => vec![],
         LogisticsState::Available(Available { locations, .. }) => locations.into_iter().collect(),
@@ -199,15 +205,11 @@ fn connected_sources(pos: hexgrid::Pos, g: &GameState) -> Vec<hexgrid::Pos> {
     }
 }
 
-pub(crate) fn update_logistics(pos: hexgrid::Pos, is_hub: bool, mut g: GameState) -> GameState {
-    let other_hubs = find_connected_hubs(pos, &mut g);
-    let connected_hubs = if is_hub {
-        let mut tv: HashSet<_> = other_hubs.collect();
-        tv.insert(pos);
-        tv
-    } else {
-        other_hubs.collect()
-    };
+pub fn update_logistics(pos: hexgrid::Pos, is_hub: bool, mut g: GameState) -> GameState {
+    let mut connected_hubs = find_connected_hubs(pos, &mut g);
+    if is_hub {
+        connected_hubs.insert(pos);
+    }
     let new_network = {
         let mut other_roads: HashSet<_> = find_connected_roads(pos, &mut g).collect();
         other_roads.insert(pos);
@@ -221,34 +223,14 @@ fn find_connected_roads(
     pos: hexgrid::Pos,
     g: &mut GameState,
 ) -> impl Iterator<Item = hexgrid::Pos> {
-    hexgrid::get_connected(
-        pos,
-        |i| match i.into() {
-            CellStateVariant::Road => true,
-            _ => false,
-        },
-        &mut g.matrix,
-    )
-    .into_iter()
-    .map(|(p, _)| p)
+    components::connected(components::Kind::Road, pos, g).into_iter()
 }
 
-fn find_connected_hubs(pos: hexgrid::Pos, g: &mut GameState) -> impl Iterator<Item = hexgrid::Pos> {
-    hexgrid::get_connected(
-        pos,
-        |i| match i.into() {
-            CellStateVariant::Hub => true,
-            CellStateVariant::Road => true,
-            _ => false,
-        },
-        &mut g.matrix,
-    )
-    .into_iter()
-    .filter(|(_p, c)| match (*c).into() {
-        CellStateVariant::Hub => true,
-        _ => false,
-    })
-    .map(|(p, _)| p)
+fn find_connected_hubs(pos: hexgrid::Pos, g: &mut GameState) -> HashSet<hexgrid::Pos> {
+    components::connected(components::Kind::Logistics, pos, g)
+        .into_iter()
+        .filter(|p| hexgrid::unsafe_get(*p, &g.matrix).variant == CellStateVariant::Hub)
+        .collect()
 }
 
 fn add_to_close(
@@ -271,7 +253,7 @@ fn add_to_close(
                     hexgrid::set(pn, new_cell, &mut acc);
                     acc
                 }
-                LogisticsState::Source { .. } => acc,
+                LogisticsState::Source => acc,
                 LogisticsState::Available(a) => {
                     let locations = new_subset.union(&a.locations).cloned().collect();
                     let new_cell = LogisticsState::Available(Available { locations, ..a });
@@ -0,0 +1,365 @@
+use std::{
+    collections::HashMap,
+    rc::Rc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use noise::{Fbm, NoiseFn, OpenSimplex, Perlin, Worley};
+
+use crate::{
+    celldata,
+    hexgrid::{self, CellGen, CHUNK_SIZE},
+    resource::{self, ResourceType},
+};
+
+// Each deposit is generated from its own noise layer,
+// to add a new one, add a ResourceType, a display scale in resource::deposit_scale
+// and an entry here
+pub const DEPOSITS: [DepositConfig; 6] = [
+    DepositConfig {
+        resource: ResourceType::Wood,
+        noise: NoiseKind::DoublePerlin,
+        frequency: 0.01,
+        threshold: 0.0,
+        max: 6,
+    },
+    DepositConfig {
+        resource: ResourceType::IronOre,
+        noise: NoiseKind::Worley,
+        frequency: 0.01,
+        threshold: 0.0,
+        max: 6,
+    },
+    DepositConfig {
+        resource: ResourceType::Stone,
+        noise: NoiseKind::Perlin,
+        frequency: 0.02,
+        threshold: 0.3,
+        max: 4,
+    },
+    DepositConfig {
+        resource: ResourceType::Copper,
+        noise: NoiseKind::Worley,
+        frequency: 0.015,
+        threshold: 0.6,
+        max: 3,
+    },
+    DepositConfig {
+        resource: ResourceType::Coal,
+        noise: NoiseKind::OpenSimplex,
+        frequency: 0.01,
+        threshold: 0.5,
+        max: 4,
+    },
+    DepositConfig {
+        resource: ResourceType::FertileSoil,
+        noise: NoiseKind::Perlin,
+        frequency: 0.005,
+        threshold: 0.2,
+        max: 5,
+    },
+];
+
+#[derive(Debug, Clone, Copy)]
+pub enum NoiseKind {
+    Perlin,
+    DoublePerlin,
+    Worley,
+    OpenSimplex,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct DepositConfig {
+    pub resource: ResourceType,
+    pub noise: NoiseKind,
+    // world cells to noise space, lower means larger features
+    pub frequency: f64,
+    // noise values below this give no deposit at all
+    pub threshold: f64,
+    // in display units, see resource::deposit_scale
+    pub max: i32,
+}
+
+#[derive(Clone)]
+enum DepositNoise {
+    Perlin(Fbm<Perlin>),
+    DoublePerlin(Fbm<Fbm<Perlin>>),
+    Worley(Fbm<Worley>),
+    OpenSimplex(Fbm<OpenSimplex>),
+}
+
+#[derive(Clone)]
+struct DepositLayer {
+    config: DepositConfig,
+    noise: DepositNoise,
+}
+
+#[derive(Clone)]
+pub struct GenContext {
+    // shared, every clone of the board carries one.
+    // The noise functions aren't Sync, and neither is the game, it lives on the UI thread
+    layers: Rc<Vec<DepositLayer>>,
+}
+
+impl CellGen for celldata::CellState {
+    type GenContext = GenContext;
+
+    // every cell is sampled from its world position, not its position in the chunk,
+    // so neighbouring chunks line up no matter which quadrant they are in
+    fn new_chunk(p: hexgrid::Pos, c: &mut Self::GenContext) -> hexgrid::Chunk<Self> {
+        let (chunk, _) = hexgrid::to_chunk_keys(p);
+        let mut ret = vec![];
+        for i in 0..CHUNK_SIZE {
+            let mut row = vec![];
+            for j in 0..CHUNK_SIZE {
+                let world_pos = chunk
+                    + hexgrid::XYCont {
+                        x: i as i32,
+                        y: j as i32,
+                    };
+                let new = resource::new_pure_stockpile(
+                    celldata::CellStateVariant::Hidden,
+                    deposits_at(c, world_pos),
+                );
+                row.push(new)
+            }
+            ret.push(row)
+        }
+        hexgrid::matrix::new(CHUNK_SIZE, CHUNK_SIZE, ret)
+    }
+}
+
+pub fn deposits_at(c: &GenContext, p: hexgrid::Pos) -> HashMap<ResourceType, i32> {
+    c.layers
+        .iter()
+        .map(|l| (l.config.resource, amount(&l.config, sample(l, p))))
+        .collect()
+}
+
+fn amount(config: &DepositConfig, value: f64) -> i32 {
+    if value < config.threshold {
+        return 0;
+    }
+    let range = max_range(config.resource) as f64;
+    let above = (value - config.threshold) / (1.0 - config.threshold);
+    (above * range).round().clamp(0.0, range) as i32
+}
+
+// the largest amount a single cell can hold of a deposit, in stockpile units
+pub fn max_range(t: ResourceType) -> i32 {
+    match DEPOSITS.iter().find(|d| d.resource == t) {
+        Some(d) => d.max * resource::deposit_scale(t),
+        None => 0,
+    }
+}
+
+fn sample(l: &DepositLayer, hexgrid::XYCont { x, y }: hexgrid::Pos) -> f64 {
+    let f = l.config.frequency;
+    let point = [x as f64 * f, y as f64 * f];
+    match &l.noise {
+        DepositNoise::Perlin(n) => n.get(point),
+        DepositNoise::DoublePerlin(n) => n.get(point),
+        DepositNoise::Worley(n) => n.get(point),
+        DepositNoise::OpenSimplex(n) => n.get(point),
+    }
+}
+
+fn new_layer(config: DepositConfig, seed: u32) -> DepositLayer {
+    let noise = match config.noise {
+        NoiseKind::Perlin => DepositNoise::Perlin(Fbm::<Perlin>::new(seed)),
+        NoiseKind::DoublePerlin => DepositNoise::DoublePerlin(Fbm::<Fbm<Perlin>>::new(seed)),
+        NoiseKind::Worley => DepositNoise::Worley(Fbm::<Worley>::new(seed)),
+        NoiseKind::OpenSimplex => DepositNoise::OpenSimplex(Fbm::<OpenSimplex>::new(seed)),
+    };
+    DepositLayer { config, noise }
+}
+
+pub fn new() -> hexgrid::Hexgrid<celldata::CellState, GenContext> {
+    let seed = if let Ok(n) = SystemTime::now().duration_since(UNIX_EPOCH) {
+        (n.as_nanos() & u32::MAX as u128) as u32
+    } else {
+        tracing::warn!("system time is before the unix epoch, using seed 0");
+        0
+    };
+    tracing::info!(seed, "new world");
+    new_with_seed(seed)
+}
+
+pub fn new_with_seed(seed: u32) -> hexgrid::Hexgrid<celldata::CellState, GenContext> {
+    hexgrid::new(
+        new_context(seed),
+        celldata::unit_state(celldata::CellStateVariant::OutOfBounds),
+    )
+}
+
+fn new_context(seed: u32) -> GenContext {
+    let layers = DEPOSITS
+        .iter()
+        .enumerate()
+        .map(|(i, config)| new_layer(*config, seed.wrapping_add(i as u32)))
+        .collect();
+    GenContext {
+        layers: Rc::new(layers),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "images")]
+    use noise::utils::NoiseMap;
+
+    use super::*;
+    use crate::{
+        celldata::{CellState, CellStateData},
+        hexgrid::{Pos, XYCont},
+    };
+
+    const SEED: u32 = 1234;
+    const CHUNK: i32 = CHUNK_SIZE as i32;
+    // seams at -CHUNK and 0 in both directions, so every quadrant around the origin is covered
+    const SEAMS: [i32; 2] = [-CHUNK, 0];
+    const ROWS: std::ops::Range<i32> = -200..200;
+
+    fn stock(c: CellState, t: ResourceType) -> i32 {
+        match c.data {
+            CellStateData::Resource(resource::Resource::Pure(r)) => resource::get(t, r),
+            a => panic!("{:?}", a),
+        }
+    }
+
+    // load everything up front, so the board can be read through a shared reference with unsafe_get
+    fn load_seams(board: &mut hexgrid::Board) {
+        let top_left = XYCont {
+            x: -2 * CHUNK,
+            y: -2 * CHUNK,
+        };
+        hexgrid::touch_all_chunks(board, top_left, 3 * CHUNK - 1, 3 * CHUNK - 1);
+    }
+
+    fn value_at(board: &hexgrid::Board, p: Pos, t: ResourceType) -> i32 {
+        stock(hexgrid::unsafe_get(p, board), t)
+    }
+
+    fn flip(XYCont { x, y }: Pos, is_vertical: bool) -> Pos {
+        if is_vertical {
+            XYCont { x, y }
+        } else {
+            XYCont { x: y, y: x }
+        }
+    }
+
+    // for eyeballing generation, needs the images feature. NoiseMap::write_to_file always writes into example_images/
+    // under the working directory, for cargo test that is the crate root, so sim/example_images/
+    #[cfg(feature = "images")]
+    fn render_region(
+        board: &hexgrid::Board,
+        top_left: Pos,
+        width: usize,
+        height: usize,
+        t: ResourceType,
+        filename: &str,
+    ) {
+        let mut map = NoiseMap::new(width, height);
+        let range = max_range(t) as f64;
+        for x in 0..width {
+            for y in 0..height {
+                let p = top_left
+                    + XYCont {
+                        x: x as i32,
+                        y: y as i32,
+                    };
+                let v = value_at(board, p, t) as f64 / range;
+                map.set_value(x, y, v * 2.0 - 1.0);
+            }
+        }
+        map.write_to_file(filename);
+    }
+
+    #[test]
+    fn chunks_match_world_position() {
+        let mut board = new_with_seed(SEED);
+        load_seams(&mut board);
+        let context = new_context(SEED);
+        for seam in SEAMS {
+            for is_vertical in [true, false] {
+                for y in ROWS {
+                    for x in [seam - 1, seam] {
+                        let p = flip(XYCont { x, y }, is_vertical);
+                        let expected = resource::new_pure_stockpile(
+                            celldata::CellStateVariant::Hidden,
+                            deposits_at(&context, p),
+                        );
+                        assert_eq!(hexgrid::unsafe_get(p, &board), expected, "{:?}", p);
+                    }
+                }
+            }
+        }
+    }
+
+    // steepest slope of a layer's noise, per unit of noise space, as measured plus some margin.
+    // Worley has none, its cells are flat patches with jumps between them anywhere on the map,
+    // its seams are covered by chunks_match_world_position
+    fn max_slope(kind: NoiseKind) -> Option<f64> {
+        match kind {
+            NoiseKind::Perlin | NoiseKind::OpenSimplex => Some(16.0),
+            NoiseKind::DoublePerlin => Some(64.0),
+            NoiseKind::Worley => None,
+        }
+    }
+
+    // the most a deposit can change from one cell to the next
+    fn max_step(d: &DepositConfig) -> Option<i32> {
+        let slope = max_slope(d.noise)?;
+        let per_cell = slope * d.frequency * max_range(d.resource) as f64 / (1.0 - d.threshold);
+        Some(per_cell.ceil() as i32)
+    }
+
+    #[test]
+    fn deposits_are_continuous_across_seams() {
+        let mut board = new_with_seed(SEED);
+        load_seams(&mut board);
+        for d in DEPOSITS.iter() {
+            let Some(bound) = max_step(d) else {
+                continue;
+            };
+            let t = d.resource;
+            for seam in SEAMS {
+                for is_vertical in [true, false] {
+                    for y in ROWS {
+                        let a = value_at(&board, flip(XYCont { x: seam - 1, y }, is_vertical), t);
+                        let b = value_at(&board, flip(XYCont { x: seam, y }, is_vertical), t);
+                        assert!(
+                            (a - b).abs() <= bound,
+                            "{:?} seam {} vertical {} row {}: {} to {}, at most {}",
+                            t,
+                            seam,
+                            is_vertical,
+                            y,
+                            a,
+                            b,
+                            bound
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    // cargo test -p sim --features images -- --ignored render_seams
+    #[cfg(feature = "images")]
+    #[test]
+    #[ignore]
+    fn render_seams() {
+        let mut board = new_with_seed(SEED);
+        let top_left = XYCont {
+            x: -CHUNK - CHUNK / 2,
+            y: -CHUNK - CHUNK / 2,
+        };
+        let size = 3 * CHUNK_SIZE;
+        hexgrid::touch_all_chunks(&mut board, top_left, size as i32 - 1, size as i32 - 1);
+        for t in DEPOSITS.iter().map(|d| d.resource) {
+            let filename = format!("seams_{:?}.png", t);
+            render_region(&board, top_left, size, size, t, &filename);
+        }
+    }
+}
@@ -1,5 +1,4 @@
 use std::collections::HashMap;
-use std::fmt;
 
 use enum_iterator::Sequence;
 
@@ -8,34 +7,38 @@ use crate::celldata::CellStateData;
 use crate::celldata::CellStateVariant;
 use crate::make_world;
 
-pub(crate) type ResourceValue = i32;
-pub(crate) type ResourceStockpile = ResourceContainer<ResourceData>;
-pub(crate) type ResourcePacket = ResourceContainer<ResourceValue>;
-pub(crate) type PacketMap = HashMap<ResourceType, i32>;
-pub(crate) type ResourceContainer<T> = [T; ResourceType::CARDINALITY as usize];
+pub type ResourceValue = i32;
+pub type ResourceStockpile = ResourceContainer<ResourceData>;
+pub type ResourcePacket = ResourceContainer<ResourceValue>;
+pub type PacketMap = HashMap<ResourceType, i32>;
+pub type ResourceContainer<T> = [T; ResourceType::CARDINALITY];
 
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Ord, Eq, Hash, Sequence)]
-pub(crate) enum ResourceType {
+pub enum ResourceType {
     LogisticsPoints = 0,
     Wood,
     Builders,
     IronOre,
     BuildTime,
+    Stone,
+    Copper,
+    Coal,
+    FertileSoil,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub(crate) struct ResourceData {
-    pub(crate) current: ResourceValue,
-    pub(crate) max: ResourceValue,
+pub struct ResourceData {
+    pub current: ResourceValue,
+    pub max: ResourceValue,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub(crate) enum Resource {
+pub enum Resource {
     Pure(ResourceStockpile),
     WithVariant(ResourceStockpile, CellStateVariant),
 }
 
-pub(crate) fn new_hub() -> CellState {
+pub fn new_hub() -> CellState {
     let cv = CellStateVariant::Hub;
     let mut r: ResourceStockpile = empty_stockpile(cv);
     r = set_to_full(ResourceType::Builders, max(cv, ResourceType::Builders), r);
@@ -48,14 +51,14 @@ pub(crate) fn new_hub() -> CellState {
     stockpile_to_cell(CellStateVariant::Hub, r)
 }
 
-pub(crate) fn new_pure_stockpile(
+pub fn new_pure_stockpile(
     cv: CellStateVariant,
     data: HashMap<ResourceType, ResourceValue>,
 ) -> CellState {
     stockpile_to_cell(cv, map_to_stockpile(cv, data))
 }
 
-pub(crate) fn new_stockpile(
+pub fn new_stockpile(
     cv: CellStateVariant,
     data: HashMap<ResourceType, ResourceValue>,
     to: CellStateVariant,
@@ -67,7 +70,7 @@ fn map_to_stockpile(
     cv: CellStateVariant,
     data: HashMap<ResourceType, ResourceValue>,
 ) -> ResourceStockpile {
-    let stockpile = data.into_iter().fold(empty_stockpile(cv), |acc, (t, d)| {
+    data.into_iter().fold(empty_stockpile(cv), |acc, (t, d)| {
         set(
             t,
             ResourceData {
@@ -76,8 +79,7 @@ fn map_to_stockpile(
             },
             acc,
         )
-    });
-    stockpile
+    })
 }
 
 fn stockpile_to_cell_with_extra_variant(
@@ -98,82 +100,95 @@ fn stockpile_to_cell(cv: CellStateVariant, s: ResourceStockpile) -> CellState {
     }
 }
 
-pub(crate) fn new_packet(builders: i32, lp: i32) -> ResourcePacket {
+pub fn new_packet(builders: i32, lp: i32) -> ResourcePacket {
     let mut ret = empty_packet();
     ret = set(ResourceType::Builders, builders, ret);
     ret = set(ResourceType::LogisticsPoints, lp, ret);
     ret
 }
 
-pub(crate) fn get(t: ResourceType, r: ResourceStockpile) -> i32 {
+pub fn get(t: ResourceType, r: ResourceStockpile) -> i32 {
     r[t as usize].current
 }
-pub(crate) fn has_capacity(t: ResourceType, r: ResourceStockpile, min_capacity: i32) -> bool {
+pub fn has_capacity(t: ResourceType, r: ResourceStockpile, min_capacity: i32) -> bool {
     let new_value = r[t as usize].current + min_capacity;
     new_value <= r[t as usize].max && new_value >= 0
 }
 
-pub(crate) fn has_resources(req: ResourcePacket, r: ResourceStockpile) -> bool {
+// how full a stockpile is over all resource types it can hold, 0.0 to 1.0
+pub fn fill_ratio(r: ResourceStockpile) -> f64 {
+    let (current, max) = all_resourcetypes()
+        .map(|i| r[i as usize])
+        .filter(|d| d.max > 0)
+        .fold((0, 0), |(c, m), d| (c + d.current, m + d.max));
+    if max == 0 {
+        0.0
+    } else {
+        current as f64 / max as f64
+    }
+}
+
+// current and max added up per resource type
+pub fn sum_stockpiles(s: impl IntoIterator<Item = ResourceStockpile>) -> ResourceStockpile {
+    let nothing = ResourceData { current: 0, max: 0 };
+    s.into_iter()
+        .fold([nothing; ResourceType::CARDINALITY], |mut acc, r| {
+            for i in all_resourcetypes() {
+                let index = i as usize;
+                acc[index].current += r[index].current;
+                acc[index].max += r[index].max;
+            }
+            acc
+        })
+}
+
+pub fn has_resources(req: ResourcePacket, r: ResourceStockpile) -> bool {
     all_resourcetypes().all(|i| req[i as usize] <= r[i as usize].current)
 }
 
-pub(crate) fn add_packet_to_packet(mut p1: ResourcePacket, p2: ResourcePacket) -> ResourcePacket {
+pub fn add_packet_to_packet(mut p1: ResourcePacket, p2: ResourcePacket) -> ResourcePacket {
     for index in all_resourcetypes() {
         let i = index as usize;
-        p1[i] = p1[i] + p2[i]
+        p1[i] += p2[i]
     }
     p1
 }
 
-pub(crate) fn neg_packet(mut p1: ResourcePacket) -> ResourcePacket {
+pub fn neg_packet(mut p1: ResourcePacket) -> ResourcePacket {
     for index in all_resourcetypes() {
         let i = index as usize;
-        p1[i] = p1[i] * -1;
+        p1[i] = -p1[i];
     }
     p1
 }
 
-pub(crate) fn add_to_packet(t: ResourceType, to_add: i32, mut p: ResourcePacket) -> ResourcePacket {
-    p[t as usize] = p[t as usize] + to_add;
+pub fn add_to_packet(t: ResourceType, to_add: i32, mut p: ResourcePacket) -> ResourcePacket {
+    p[t as usize] += to_add;
     p
 }
 
-pub(crate) fn add_packet(p: ResourcePacket, c: CellState) -> Option<CellState> {
+pub fn add_packet(p: ResourcePacket, c: CellState) -> Option<CellState> {
     match c {
         CellState {
             variant: cv,
             data: CellStateData::Resource(Resource::Pure(resources)),
-        } => {
-            if let Some(s) = add_packet_stockpile(p, resources) {
-                Some(stockpile_to_cell(cv, s))
-            } else {
-                None
-            }
-        }
+        } => add_packet_stockpile(p, resources).map(|s| stockpile_to_cell(cv, s)),
         CellState {
             variant: cv,
             data: CellStateData::Resource(Resource::WithVariant(resources, cv2)),
-        } => {
-            if let Some(s) = add_packet_stockpile(p, resources) {
-                Some(stockpile_to_cell_with_extra_variant(cv, s, cv2))
-            } else {
-                None
-            }
-        }
+        } => add_packet_stockpile(p, resources)
+            .map(|s| stockpile_to_cell_with_extra_variant(cv, s, cv2)),
         _ => None,
     }
 }
 
-pub(crate) fn add(t: ResourceType, c: CellState, to_add: i32) -> Option<CellState> {
+pub fn add(t: ResourceType, c: CellState, to_add: i32) -> Option<CellState> {
     let mut p = empty_packet();
     p = set(t, to_add, p);
     add_packet(p, c)
 }
 
-pub(crate) fn to_key_value_display_amounts(
-    cv: CellStateVariant,
-    r: ResourceStockpile,
-) -> PacketMap {
+pub fn to_key_value_display_amounts(cv: CellStateVariant, r: ResourceStockpile) -> PacketMap {
     let mut ret = HashMap::new();
     for i in all_resourcetypes() {
         let value0 = get(i, r);
@@ -185,7 +200,19 @@ pub(crate) fn to_key_value_display_amounts(
     ret
 }
 
-pub(crate) fn to_key_value(r: ResourceStockpile) -> PacketMap {
+// current and max in display units, for every type the stockpile holds or can hold
+pub fn to_display_rows(
+    cv: CellStateVariant,
+    r: ResourceStockpile,
+) -> Vec<(ResourceType, ResourceValue, ResourceValue)> {
+    all_resourcetypes()
+        .map(|i| (i, r[i as usize]))
+        .filter(|(_, d)| d.current != 0 || d.max != 0)
+        .map(|(i, d)| (i, scale(cv, i, d.current), scale(cv, i, d.max)))
+        .collect()
+}
+
+pub fn to_key_value(r: ResourceStockpile) -> PacketMap {
     let mut ret = HashMap::new();
     for i in all_resourcetypes() {
         let value = get(i, r);
@@ -196,15 +223,28 @@ pub(crate) fn to_key_value(r: ResourceStockpile) -> PacketMap {
     ret
 }
 
-fn scale(cv: CellStateVariant, _t: ResourceType, value: i32) -> i32 {
+fn scale(cv: CellStateVariant, t: ResourceType, value: i32) -> i32 {
     let factor = match cv {
-        CellStateVariant::Hidden => make_world::SCALING_WOOD,
+        CellStateVariant::Hidden => deposit_scale(t),
         _ => 1,
     };
     value / factor
 }
 
-pub(crate) fn from_key_value(map: PacketMap) -> ResourcePacket {
+// how many stockpile units of a world generated deposit make up one displayed unit
+pub fn deposit_scale(t: ResourceType) -> i32 {
+    match t {
+        ResourceType::Wood => 16,
+        ResourceType::IronOre => 16,
+        ResourceType::Stone => 16,
+        ResourceType::Copper => 24,
+        ResourceType::Coal => 20,
+        ResourceType::FertileSoil => 8,
+        _ => 1,
+    }
+}
+
+pub fn from_key_value(map: PacketMap) -> ResourcePacket {
     let mut ret = empty_packet();
     for (t, v) in map {
         ret = set(t, v, ret)
@@ -220,12 +260,12 @@ fn add_packet_stockpile(p: ResourcePacket, mut s: ResourceStockpile) -> Option<R
             continue;
         }
         if has_capacity(i, s, delta) {
-            s[index].current = s[index].current + delta;
+            s[index].current += delta;
         } else {
             return None;
         }
     }
-    return Some(s);
+    Some(s)
 }
 
 fn max(cv: CellStateVariant, t: ResourceType) -> i32 {
@@ -235,8 +275,8 @@ fn max(cv: CellStateVariant, t: ResourceType) -> i32 {
         (CellStateVariant::Hub, ResourceType::Builders) => 3,
         (CellStateVariant::Building, ResourceType::BuildTime) => 10,
         (CellStateVariant::Building, ResourceType::Builders) => 2,
-        (CellStateVariant::Hidden, ResourceType::Wood) => make_world::MAX_WOOD_RANGE,
-        (CellStateVariant::Unused, ResourceType::Wood) => make_world::MAX_WOOD_RANGE,
+        (CellStateVariant::Hidden, t) => make_world::max_range(t),
+        (CellStateVariant::Unused, t) => make_world::max_range(t),
         _ => 0,
     }
 }
@@ -245,14 +285,14 @@ fn all_resourcetypes() -> impl Iterator<Item = ResourceType> {
     enum_iterator::all::<ResourceType>()
 }
 
-pub(crate) fn empty_packet() -> ResourcePacket {
+pub fn empty_packet() -> ResourcePacket {
     let nothing = 0;
-    [nothing; ResourceType::CARDINALITY as usize]
+    [nothing; ResourceType::CARDINALITY]
 }
 
-pub(crate) fn empty_stockpile(cv: CellStateVariant) -> ResourceStockpile {
+pub fn empty_stockpile(cv: CellStateVariant) -> ResourceStockpile {
     let nothing = ResourceData { current: 0, max: 0 };
-    let mut ret = [nothing; ResourceType::CARDINALITY as usize];
+    let mut ret = [nothing; ResourceType::CARDINALITY];
     for i in all_resourcetypes() {
         ret[i as usize].max = max(cv, i);
     }
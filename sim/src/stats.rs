@@ -0,0 +1,108 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use crate::{
+    celldata::{CellStateData, CellStateVariant},
+    components, hexgrid,
+    resource::{self, Resource, ResourceType},
+    GameState,
+};
+
+// One entry per turn, the first one is the state the game started in
+pub type StatsLog = im::Vector<TurnStats>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TurnStats {
+    pub turn: u32,
+    // summed over every hub
+    pub hub_resources: resource::PacketMap,
+    pub tiles: i32,
+    pub leak: i32,
+    pub heat_efficency: f64,
+    pub buildings: HashMap<CellStateVariant, i32>,
+    // builders sitting in a hub, not lent out to a building site
+    pub idle_builders: i32,
+}
+
+pub fn new_log() -> StatsLog {
+    im::Vector::new()
+}
+
+pub fn is_building(cv: CellStateVariant) -> bool {
+    !matches!(
+        cv,
+        CellStateVariant::Menu
+            | CellStateVariant::Hidden
+            | CellStateVariant::Unused
+            | CellStateVariant::OutOfBounds
+            | CellStateVariant::Back
+            | CellStateVariant::Last
+    )
+}
+
+pub fn building_variants() -> impl Iterator<Item = CellStateVariant> {
+    enum_iterator::all::<CellStateVariant>().filter(|cv| is_building(*cv))
+}
+
+// only reads the hubs, the buildings are counted by components::set as they change
+pub fn collect(g: &GameState) -> TurnStats {
+    let mut hub_resources = HashMap::new();
+    for h in components::buildings(CellStateVariant::Hub, g) {
+        if let CellStateData::Resource(Resource::Pure(r)) = hexgrid::unsafe_get(h, &g.matrix).data {
+            for (t, v) in resource::to_key_value(r) {
+                *hub_resources.entry(t).or_insert(0) += v;
+            }
+        }
+    }
+    let buildings = building_variants()
+        .map(|cv| (cv, components::building_count(cv, g) as i32))
+        .filter(|(_, n)| *n > 0)
+        .collect();
+    TurnStats {
+        turn: g.turn,
+        idle_builders: *hub_resources.get(&ResourceType::Builders).unwrap_or(&0),
+        hub_resources,
+        tiles: g.resources.tiles,
+        leak: g.resources.leak,
+        heat_efficency: g.resources.heat_efficency,
+        buildings,
+    }
+}
+
+pub fn record(mut g: GameState) -> GameState {
+    let s = collect(&g);
+    g.stats.push_back(s);
+    g
+}
+
+pub fn to_csv(log: &StatsLog) -> String {
+    let mut header = vec![
+        "turn".to_string(),
+        "tiles".to_string(),
+        "leak".to_string(),
+        "heat_efficency".to_string(),
+        "idle_builders".to_string(),
+    ];
+    header.extend(enum_iterator::all::<ResourceType>().map(|t| format!("hub_{:?}", t)));
+    header.extend(building_variants().map(|cv| format!("buildings_{:?}", cv)));
+    let mut lines = vec![header.join(",")];
+    for s in log {
+        let mut row = vec![
+            s.turn.to_string(),
+            s.tiles.to_string(),
+            s.leak.to_string(),
+            s.heat_efficency.to_string(),
+            s.idle_builders.to_string(),
+        ];
+        row.extend(
+            enum_iterator::all::<ResourceType>()
+                .map(|t| s.hub_resources.get(&t).unwrap_or(&0).to_string()),
+        );
+        row.extend(building_variants().map(|cv| s.buildings.get(&cv).unwrap_or(&0).to_string()));
+        lines.push(row.join(","));
+    }
+    lines.join("\n") + "\n"
+}
+
+pub fn write_csv(log: &StatsLog, path: &Path) -> std::io::Result<()> {
+    fs::write(path, to_csv(log))
+}
@@ -0,0 +1,258 @@
+use std::{
+    collections::{HashMap, HashSet},
+    env, fmt,
+};
+
+use crate::{
+    actionmachine,
+    celldata::{self, CellState, CellStateData, CellStateVariant},
+    components,
+    hexgrid::{self, Pos},
+    logistics_plane::LogisticsState,
+    resource::{Resource, ResourceData, ResourceType, ResourceValue},
+    stats, GameState, START_LEAK,
+};
+
+// GAME_VALIDATE=1 checks the game state after every turn, GAME_VALIDATE=0 doesn't.
+// Unset it is on in debug builds and off in release builds
+pub const VAR: &str = "GAME_VALIDATE";
+
+// hubs start with these full and only ever lend them out
+const LENT: [ResourceType; 2] = [ResourceType::Builders, ResourceType::LogisticsPoints];
+
+pub fn enabled() -> bool {
+    match env::var(VAR) {
+        Ok(v) => v != "0",
+        Err(_) => cfg!(debug_assertions),
+    }
+}
+
+// Bookkeeping that has to add up after every turn, any of these is a bug in the game logic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Violation {
+    // a stockpile holds less than nothing or more than fits
+    OutOfRange(Pos, CellStateVariant, ResourceType, ResourceData),
+    // what a hub has and has lent out doesn't add up to what it started with
+    NotConserved {
+        hub: Pos,
+        t: ResourceType,
+        current: ResourceValue,
+        lent: ResourceValue,
+        max: ResourceValue,
+    },
+    // the action machine runs pos at prio but the cell there has no business being run there
+    WrongPrio(Pos, actionmachine::Prio, CellStateVariant),
+    // the logistics plane at pos reaches or borrows from hub, but it isn't a hub
+    NotAHub {
+        at: Pos,
+        hub: Pos,
+    },
+    Leak {
+        stored: i32,
+        recomputed: i32,
+    },
+    Tiles {
+        stored: i32,
+        recomputed: i32,
+    },
+    // the component index counts a different number of cv than are on the board
+    Buildings {
+        cv: CellStateVariant,
+        stored: usize,
+        recomputed: usize,
+    },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Violation::OutOfRange(p, cv, t, d) => write!(
+                f,
+                "{} at {}, {} has {} of {} {:?}",
+                cv, p.x, p.y, d.current, d.max, t
+            ),
+            Violation::NotConserved {
+                hub,
+                t,
+                current,
+                lent,
+                max,
+            } => write!(
+                f,
+                "hub at {}, {} has {} {:?} and {} lent out, started with {}",
+                hub.x, hub.y, current, t, lent, max
+            ),
+            Violation::WrongPrio(p, prio, cv) => write!(
+                f,
+                "{} at {}, {} is in the action machine at prio {}",
+                cv, p.x, p.y, prio
+            ),
+            Violation::NotAHub { at, hub } => write!(
+                f,
+                "logistics at {}, {} use {}, {} which is not a hub",
+                at.x, at.y, hub.x, hub.y
+            ),
+            Violation::Leak { stored, recomputed } => {
+                write!(f, "leak is {} but should be {}", stored, recomputed)
+            }
+            Violation::Tiles { stored, recomputed } => {
+                write!(
+                    f,
+                    "tiles is {} but there are {} Hot cells",
+                    stored, recomputed
+                )
+            }
+            Violation::Buildings {
+                cv,
+                stored,
+                recomputed,
+            } => write!(
+                f,
+                "{} {} cells are counted but there are {}",
+                stored, cv, recomputed
+            ),
+        }
+    }
+}
+
+// Walks every loaded cell, only meant for debugging
+pub fn validate(g: &GameState) -> Result<(), Vec<Violation>> {
+    let _span = tracing::debug_span!("validate", turn = g.turn).entered();
+    let mut ret = vec![];
+    let mut hubs = HashMap::new();
+    let mut leaky = vec![];
+    let mut buildings: HashMap<CellStateVariant, usize> = HashMap::new();
+    for (p, c) in hexgrid::loaded_cells(&g.matrix) {
+        if stats::is_building(c.variant) {
+            *buildings.entry(c.variant).or_insert(0) += 1;
+        }
+        if celldata::has_leak(c.variant) {
+            leaky.push((p, *c));
+        }
+        let r = match c.data {
+            CellStateData::Resource(Resource::Pure(r) | Resource::WithVariant(r, _)) => r,
+            _ => continue,
+        };
+        for t in enum_iterator::all::<ResourceType>() {
+            let d = r[t as usize];
+            if d.current < 0 || d.current > d.max {
+                ret.push(Violation::OutOfRange(p, c.variant, t, d));
+            }
+        }
+        if c.variant == CellStateVariant::Hub {
+            hubs.insert(p, r);
+        }
+    }
+
+    let mut lent: HashMap<(Pos, ResourceType), ResourceValue> = HashMap::new();
+    for (p, l) in hexgrid::loaded_cells(&g.logistics_plane) {
+        let LogisticsState::Available(a) = l else {
+            continue;
+        };
+        let used = a
+            .locations
+            .iter()
+            .chain(a.borrows.keys())
+            .chain(a.taken_lp.keys());
+        for h in used.collect::<HashSet<_>>() {
+            if !hubs.contains_key(h) {
+                ret.push(Violation::NotAHub { at: p, hub: *h });
+            }
+        }
+        // borrowed packets are stored as taken from the hub, so negative
+        for (h, b) in a.borrows.iter() {
+            for t in LENT {
+                *lent.entry((*h, t)).or_insert(0) -= b[t as usize];
+            }
+        }
+        for (h, v) in a.taken_lp.iter() {
+            *lent.entry((*h, ResourceType::LogisticsPoints)).or_insert(0) += v;
+        }
+    }
+    for (h, r) in hubs.iter() {
+        for t in LENT {
+            let d = r[t as usize];
+            let lent = lent.get(&(*h, t)).cloned().unwrap_or(0);
+            if d.current + lent != d.max {
+                ret.push(Violation::NotConserved {
+                    hub: *h,
+                    t,
+                    current: d.current,
+                    lent,
+                    max: d.max,
+                });
+            }
+        }
+    }
+
+    for (prio, positions) in g.action_machine.iter().enumerate() {
+        for p in positions.iter() {
+            let cv = hexgrid::unsafe_get(*p, &g.matrix).variant;
+            if actionmachine::prio(cv) != Some(prio) {
+                ret.push(Violation::WrongPrio(*p, prio, cv));
+            }
+        }
+    }
+
+    let recomputed = recompute_leak(&leaky, g);
+    if recomputed != g.resources.leak {
+        ret.push(Violation::Leak {
+            stored: g.resources.leak,
+            recomputed,
+        });
+    }
+    let hot = leaky
+        .iter()
+        .filter(|(_, c)| celldata::is_hot_v(c.variant))
+        .count() as i32;
+    if hot != g.resources.tiles {
+        ret.push(Violation::Tiles {
+            stored: g.resources.tiles,
+            recomputed: hot,
+        });
+    }
+
+    for cv in stats::building_variants() {
+        let stored = components::building_count(cv, g);
+        let recomputed = buildings.get(&cv).cloned().unwrap_or(0);
+        if stored != recomputed {
+            ret.push(Violation::Buildings {
+                cv,
+                stored,
+                recomputed,
+            });
+        }
+    }
+
+    if ret.is_empty() {
+        Ok(())
+    } else {
+        Err(ret)
+    }
+}
+
+// Builds the leaking cells again one by one on a copy of the board.
+// Every pair of neighbours is counted once by whichever is built second, so the order doesn't matter
+fn recompute_leak(leaky: &[(Pos, CellState)], g: &GameState) -> i32 {
+    let mut m = g.matrix.clone();
+    for (p, _) in leaky {
+        hexgrid::set(*p, celldata::unit_state(CellStateVariant::Unused), &mut m);
+    }
+    leaky.iter().fold(START_LEAK, |acc, (p, c)| {
+        hexgrid::set(*p, *c, &mut m);
+        acc + celldata::leak_delta(c.variant, *p, &mut m).unwrap_or(0)
+    })
+}
+
+// logs every violation, true if there were none
+pub fn report(g: &GameState) -> bool {
+    match validate(g) {
+        Ok(()) => true,
+        Err(violations) => {
+            for v in violations.iter() {
+                tracing::error!(turn = g.turn, "{}", v);
+            }
+            false
+        }
+    }
+}
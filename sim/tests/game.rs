@@ -0,0 +1,335 @@
+use std::collections::HashMap;
+
+use sim::{
+    actionmachine, build_queue, building,
+    celldata::{self, CellStateData, CellStateVariant},
+    components, events,
+    hexgrid::{self, Pos, XYCont},
+    logistics_plane::{self, LogisticsState},
+    make_world, new_game, resource,
+    resource::{Resource, ResourceType},
+    validate, GameState,
+};
+
+// Whole games played without the UI, the way a player would through the build queue,
+// with the invariants checked after every turn
+
+const SEED: u32 = 1234;
+const START_HUB: Pos = XYCont { x: 0, y: 0 };
+// turns a single blueprint may take before the test gives up on it
+const MAX_WAIT: u32 = 60;
+
+fn new_test_game() -> GameState {
+    new_game(make_world::new_with_seed(SEED))
+}
+
+fn check_invariants(g: &GameState) {
+    let turn = g.turn;
+    if let Err(violations) = validate::validate(g) {
+        let lines: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+        panic!("turn {}:\n{}", turn, lines.join("\n"));
+    }
+    let mut borrowed: HashMap<Pos, i32> = HashMap::new();
+    for (p, l) in hexgrid::loaded_cells(&g.logistics_plane) {
+        if let LogisticsState::Available(a) = l {
+            // builders are returned when the building is finished
+            if !a.borrows.is_empty() {
+                let c = hexgrid::unsafe_get(p, &g.matrix);
+                assert_eq!(
+                    c.variant,
+                    CellStateVariant::Building,
+                    "turn {}: {:?} still borrows {:?}",
+                    turn,
+                    p,
+                    a.borrows
+                );
+            }
+            for (h, b) in a.borrows.iter() {
+                *borrowed.entry(*h).or_insert(0) -= b[ResourceType::Builders as usize];
+            }
+        }
+    }
+    for (p, c) in hexgrid::loaded_cells(&g.matrix) {
+        let r = match c.data {
+            CellStateData::Resource(Resource::Pure(r) | Resource::WithVariant(r, _)) => r,
+            _ => continue,
+        };
+        for t in enum_iterator::all::<ResourceType>() {
+            let d = r[t as usize];
+            assert!(
+                0 <= d.current && d.current <= d.max,
+                "turn {}: {:?} at {:?} has {:?} {:?}",
+                turn,
+                c.variant,
+                p,
+                t,
+                d
+            );
+        }
+        if c.variant == CellStateVariant::Hub {
+            let builders = r[ResourceType::Builders as usize];
+            assert_eq!(
+                builders.current + borrowed.get(&p).cloned().unwrap_or(0),
+                builders.max,
+                "turn {}: builders of the hub at {:?} went missing",
+                turn,
+                p
+            );
+        }
+    }
+}
+
+fn turn(g: GameState) -> GameState {
+    let g = actionmachine::run(g).unwrap_or_else(|e| panic!("turn failed: {}", e));
+    check_invariants(&g);
+    g
+}
+
+fn turns(n: u32, mut g: GameState) -> GameState {
+    for _ in 0..n {
+        g = turn(g);
+    }
+    g
+}
+
+// queues cv at pos and runs turns until it has been built
+fn build(cv: CellStateVariant, pos: Pos, mut g: GameState) -> GameState {
+    g = build_queue::place(cv, pos, g);
+    for _ in 0..MAX_WAIT {
+        g = turn(g);
+        let c = hexgrid::unsafe_get(pos, &g.matrix);
+        if build_queue::queued_at(pos, &g).is_none() && c.variant != CellStateVariant::Building {
+            assert_eq!(
+                c.variant, cv,
+                "turn {}: building {} at {:?}",
+                g.turn, cv, pos
+            );
+            return g;
+        }
+    }
+    panic!("{} at {:?} not built after {} turns", cv, pos, MAX_WAIT);
+}
+
+// explores a hidden cell and goes through the menu to cv
+fn build_from_hidden(
+    menu: CellStateVariant,
+    cv: CellStateVariant,
+    pos: Pos,
+    g: GameState,
+) -> GameState {
+    let g = build(CellStateVariant::Unused, pos, g);
+    let g = build(menu, pos, g);
+    build(cv, pos, g)
+}
+
+fn stock(t: ResourceType, pos: Pos, g: &GameState) -> i32 {
+    match hexgrid::unsafe_get(pos, &g.matrix).data {
+        CellStateData::Resource(Resource::Pure(r)) => resource::get(t, r),
+        a => panic!("{:?}", a),
+    }
+}
+
+fn finished(cv: CellStateVariant, pos: Pos, g: &GameState) -> bool {
+    g.events
+        .iter()
+        .any(|e| e.event == events::GameEvent::BuildFinished(cv, pos))
+}
+
+#[test]
+fn new_game_is_valid() {
+    let g = new_test_game();
+    check_invariants(&g);
+    assert_eq!(stock(ResourceType::Wood, START_HUB, &g), 10);
+    turns(5, g);
+}
+
+// a road out of reach of the start hub, a farm at its end and a second hub paid for by the farm
+#[test]
+fn road_farm_and_hub() {
+    let road: Vec<Pos> = hexgrid::line(XYCont { x: 0, y: 1 }, XYCont { x: 0, y: 3 }).collect();
+    let farm = XYCont { x: 0, y: 6 };
+    let hub = XYCont { x: 1, y: 5 };
+    let mut g = new_test_game();
+    assert!(!logistics_plane::has_worker(farm, &g));
+    for p in road.iter() {
+        g = build_from_hidden(
+            CellStateVariant::Infrastructure,
+            CellStateVariant::Road,
+            *p,
+            g,
+        );
+    }
+    assert_eq!(
+        logistics_plane::connected_sources(farm, &g),
+        vec![START_HUB]
+    );
+    g = build_from_hidden(
+        CellStateVariant::Extract,
+        CellStateVariant::WoodFarm,
+        farm,
+        g,
+    );
+    let wood = stock(ResourceType::Wood, START_HUB, &g);
+    g = turns(
+        2 * actionmachine::in_progress_max(CellStateVariant::WoodFarm).unwrap(),
+        g,
+    );
+    assert!(stock(ResourceType::Wood, START_HUB, &g) > wood);
+
+    g = build_from_hidden(
+        CellStateVariant::Infrastructure,
+        CellStateVariant::Hub,
+        hub,
+        g,
+    );
+    assert!(finished(CellStateVariant::Hub, hub, &g));
+    assert_eq!(
+        hexgrid::unsafe_get(hub, &g.logistics_plane),
+        LogisticsState::Source
+    );
+    // with nothing left to build every builder is back home
+    g = turns(5, g);
+    for h in [START_HUB, hub] {
+        assert_eq!(stock(ResourceType::Builders, h, &g), 3);
+    }
+}
+
+// a feeder fills the Hot cells next to it and a seller empties them again
+#[test]
+fn feeder_and_seller() {
+    let hot = [XYCont { x: 1, y: 1 }, XYCont { x: 1, y: 2 }];
+    let feeder = XYCont { x: 2, y: 1 };
+    let seller = XYCont { x: 2, y: 2 };
+    let mut g = new_test_game();
+    for p in hot {
+        g = build_from_hidden(CellStateVariant::Industry, CellStateVariant::Hot, p, g);
+    }
+    g = build_from_hidden(
+        CellStateVariant::Industry,
+        CellStateVariant::Feeder,
+        feeder,
+        g,
+    );
+    g = build_from_hidden(
+        CellStateVariant::Extract,
+        CellStateVariant::Seller,
+        seller,
+        g,
+    );
+    g = turns(
+        4 * actionmachine::in_progress_max(CellStateVariant::Hot).unwrap(),
+        g,
+    );
+    let sold = g
+        .events
+        .iter()
+        .filter(|e| matches!(e.event, events::GameEvent::Sold(_)))
+        .count();
+    assert!(sold >= 2, "{} sold", sold);
+    assert_eq!(g.resources.tiles, hot.len() as i32);
+}
+
+// the start hub only has the wood for a single build action of a hub, after that the site stalls
+// and that is logged once, not every turn
+#[test]
+fn stall_is_logged_once() {
+    let site = XYCont { x: 1, y: 0 };
+    let mut g = new_test_game();
+    g = build(CellStateVariant::Unused, site, g);
+    g = build(CellStateVariant::Infrastructure, site, g);
+    g = build_queue::place(CellStateVariant::Hub, site, g);
+    g = turns(10, g);
+    let stalled = g
+        .events
+        .iter()
+        .filter(|e| e.event == events::GameEvent::BuildStalled(CellStateVariant::Hub, site))
+        .count();
+    assert_eq!(stalled, 1);
+    assert!(g.stuck.contains(&site));
+}
+
+// blueprints out of reach wait for a hub, ones their cell no longer offers are dropped
+#[test]
+fn blueprints_wait_or_are_dropped() {
+    let far = XYCont { x: 0, y: 20 };
+    let near = XYCont { x: 1, y: 1 };
+    let mut g = new_test_game();
+    assert!(logistics_plane::connected_sources(far, &g).is_empty());
+    g = build_queue::place(CellStateVariant::Unused, far, g);
+    g = build_from_hidden(
+        CellStateVariant::Infrastructure,
+        CellStateVariant::Road,
+        near,
+        g,
+    );
+    assert_eq!(
+        build_queue::queued_at(far, &g).map(|(_, b)| b.blocker),
+        Some(Some(building::Blocker::NotConnected))
+    );
+
+    let near = XYCont { x: 1, y: 0 };
+    g = build(CellStateVariant::Unused, near, g);
+    g = build(CellStateVariant::Infrastructure, near, g);
+    g = build_queue::place(CellStateVariant::Hub, near, g);
+    g = building::build(CellStateVariant::Back, near, g).unwrap();
+    g = turn(g);
+    assert!(build_queue::queued_at(near, &g).is_none());
+    assert_eq!(
+        g.events.last().map(|e| e.event),
+        Some(events::GameEvent::BlueprintDropped(
+            CellStateVariant::Hub,
+            near
+        ))
+    );
+    assert!(build_queue::queued_at(far, &g).is_some());
+}
+
+#[test]
+fn validate_finds_broken_bookkeeping() {
+    let g = new_test_game();
+    assert_eq!(validate::validate(&g), Ok(()));
+
+    let mut g1 = g.clone();
+    let hub = hexgrid::get(START_HUB, &mut g1.matrix);
+    let hub = resource::add(ResourceType::Builders, hub, -1).unwrap();
+    hexgrid::set(START_HUB, hub, &mut g1.matrix);
+    assert!(matches!(
+        validate::validate(&g1).unwrap_err()[..],
+        [validate::Violation::NotConserved {
+            t: ResourceType::Builders,
+            ..
+        }]
+    ));
+
+    let mut g1 = g.clone();
+    g1.resources.leak += 1;
+    assert!(matches!(
+        validate::validate(&g1).unwrap_err()[..],
+        [validate::Violation::Leak { .. }]
+    ));
+
+    let mut g1 = g.clone();
+    let p = XYCont { x: 1, y: 1 };
+    g1.action_machine = actionmachine::maybe_insert(g1.action_machine, p, CellStateVariant::Hot);
+    assert!(matches!(
+        validate::validate(&g1).unwrap_err()[..],
+        [validate::Violation::WrongPrio(..)]
+    ));
+
+    // a road next to the hub reaches it, turning the hub into a road leaves the road reaching nothing
+    let mut g1 = build_from_hidden(
+        CellStateVariant::Infrastructure,
+        CellStateVariant::Road,
+        XYCont { x: 0, y: 1 },
+        g,
+    );
+    components::set(
+        START_HUB,
+        celldata::unit_state(CellStateVariant::Road),
+        &mut g1,
+    );
+    let violations = validate::validate(&g1).unwrap_err();
+    assert!(violations
+        .iter()
+        .all(|v| matches!(v, validate::Violation::NotAHub { hub: START_HUB, .. })));
+}
@@ -0,0 +1,45 @@
+use iced::widget::{button, column, container, row, text};
+use iced_native::Length;
+
+use crate::{css, widget, GameState, Message};
+use widget::Element;
+
+pub(crate) use sim::build_queue::*;
+
+const TEXT_SIZE: u16 = 16;
+
+pub(crate) fn view<'a>(g: &GameState) -> Element<'a, Message> {
+    let mut lines = vec![crate::Element::from(
+        text(format!("Build queue ({})", g.build_queue.len())).size(TEXT_SIZE),
+    )];
+    for (i, b) in g.build_queue.iter().enumerate() {
+        let waiting = match b.blocker {
+            Some(blocker) => format!(" ({})", blocker),
+            None => String::new(),
+        };
+        lines.push(crate::Element::from(
+            row![
+                text(format!(
+                    "{}. {} at {}, {}{}",
+                    i + 1,
+                    b.cv,
+                    b.pos.x,
+                    b.pos.y,
+                    waiting
+                ))
+                .size(TEXT_SIZE)
+                .width(Length::Fill),
+                button(text("^").size(TEXT_SIZE)).on_press(Message::MoveBlueprint(i, true)),
+                button(text("v").size(TEXT_SIZE)).on_press(Message::MoveBlueprint(i, false)),
+                button(text("x").size(TEXT_SIZE)).on_press(Message::RemoveBlueprint(i)),
+            ]
+            .spacing(2),
+        ));
+    }
+    crate::Element::from(
+        container(column(lines).spacing(2))
+            .width(Length::Fill)
+            .padding(4)
+            .style(css::Container::Bordered),
+    )
+}
@@ -0,0 +1,185 @@
+use iced::{
+    alignment::{Horizontal, Vertical},
+    widget::canvas::{self, Canvas, Cursor, Frame, Geometry, Path, Stroke, Text},
+    Color, Point, Rectangle,
+};
+use iced_native::Length;
+
+use crate::{
+    css,
+    resource::ResourceType,
+    stats::{self, StatsLog},
+    widget, Message,
+};
+use widget::Element;
+
+const CHART_HEIGHT: f32 = 200.0;
+const MARGIN: f32 = 40.0;
+const TEXT_SIZE: f32 = 14.0;
+// picked in order, series past the end reuse them
+const LINE_COLORS: [Color; 6] = [
+    Color::from_rgb(1.0, 1.0, 1.0),
+    Color::from_rgb(1.0, 0.8, 0.2),
+    Color::from_rgb(0.4, 0.7, 1.0),
+    Color::from_rgb(1.0, 0.4, 0.4),
+    Color::from_rgb(0.6, 1.0, 0.6),
+    Color::from_rgb(0.9, 0.5, 1.0),
+];
+
+struct Series {
+    name: String,
+    values: Vec<f32>,
+}
+
+struct LineChart {
+    title: String,
+    series: Vec<Series>,
+}
+
+// one chart per group of related numbers, series that stayed at zero are left out
+pub(crate) fn view<'a>(log: &StatsLog) -> Element<'a, Message> {
+    let series = |name: &str, f: &dyn Fn(&stats::TurnStats) -> f32| Series {
+        name: name.to_string(),
+        values: log.iter().map(f).collect(),
+    };
+    let charts = vec![
+        LineChart {
+            title: "Heat".to_string(),
+            series: vec![
+                series("tiles", &|s| s.tiles as f32),
+                series("leak", &|s| s.leak as f32),
+            ],
+        },
+        LineChart {
+            title: "Heat efficency".to_string(),
+            series: vec![series("efficency", &|s| s.heat_efficency as f32)],
+        },
+        LineChart {
+            title: "Hub resources".to_string(),
+            series: enum_iterator::all::<ResourceType>()
+                .map(|t| {
+                    series(&format!("{:?}", t), &|s| {
+                        *s.hub_resources.get(&t).unwrap_or(&0) as f32
+                    })
+                })
+                .collect(),
+        },
+        LineChart {
+            title: "Buildings".to_string(),
+            series: stats::building_variants()
+                .map(|cv| {
+                    series(&cv.to_string(), &|s| {
+                        *s.buildings.get(&cv).unwrap_or(&0) as f32
+                    })
+                })
+                .collect(),
+        },
+        LineChart {
+            title: "Idle builders".to_string(),
+            series: vec![series("builders", &|s| s.idle_builders as f32)],
+        },
+    ];
+    let elements = charts
+        .into_iter()
+        .map(|mut c| {
+            c.series.retain(|s| s.values.iter().any(|v| *v != 0.0));
+            crate::Element::from(
+                Canvas::new(c)
+                    .width(Length::Fill)
+                    .height(Length::Fixed(CHART_HEIGHT)),
+            )
+        })
+        .collect();
+    crate::Element::from(iced::widget::Column::with_children(elements).spacing(10))
+}
+
+impl canvas::Program<Message, css::Theme> for LineChart {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        _theme: &css::Theme,
+        bounds: Rectangle,
+        _cursor: Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(bounds.size());
+        let plot = Rectangle {
+            x: MARGIN,
+            y: MARGIN / 2.0,
+            width: bounds.width - MARGIN * 2.0,
+            height: bounds.height - MARGIN,
+        };
+        frame.fill_text(Text {
+            content: self.title.clone(),
+            position: Point::new(plot.x, 0.0),
+            size: TEXT_SIZE,
+            color: Color::WHITE,
+            ..Default::default()
+        });
+        let axes = Path::new(|b| {
+            b.move_to(Point::new(plot.x, plot.y));
+            b.line_to(Point::new(plot.x, plot.y + plot.height));
+            b.line_to(Point::new(plot.x + plot.width, plot.y + plot.height));
+        });
+        frame.stroke(&axes, Stroke::default().with_color(Color::WHITE));
+
+        let len = self
+            .series
+            .iter()
+            .map(|s| s.values.len())
+            .max()
+            .unwrap_or(0);
+        let (min, max) = self
+            .series
+            .iter()
+            .flat_map(|s| s.values.iter())
+            .fold((0.0_f32, 0.0_f32), |(lo, hi), v| (lo.min(*v), hi.max(*v)));
+        // a flat line still needs a range to be drawn in
+        let range = if max > min { max - min } else { 1.0 };
+        let to_point = |i: usize, v: f32| {
+            let x = if len > 1 {
+                i as f32 / (len - 1) as f32
+            } else {
+                0.0
+            };
+            Point::new(
+                plot.x + x * plot.width,
+                plot.y + plot.height - (v - min) / range * plot.height,
+            )
+        };
+        for value in [max, min] {
+            frame.fill_text(Text {
+                content: format!("{}", value),
+                position: Point::new(plot.x - 4.0, to_point(0, value).y),
+                size: TEXT_SIZE,
+                color: Color::WHITE,
+                horizontal_alignment: Horizontal::Right,
+                vertical_alignment: Vertical::Center,
+                ..Default::default()
+            });
+        }
+
+        for (index, s) in self.series.iter().enumerate() {
+            let color = LINE_COLORS[index % LINE_COLORS.len()];
+            if s.values.len() > 1 {
+                let line = Path::new(|b| {
+                    b.move_to(to_point(0, s.values[0]));
+                    for (i, v) in s.values.iter().enumerate().skip(1) {
+                        b.line_to(to_point(i, *v));
+                    }
+                });
+                frame.stroke(&line, Stroke::default().with_color(color).with_width(2.0));
+            }
+            let last = s.values.last().cloned().unwrap_or(0.0);
+            frame.fill_text(Text {
+                content: format!("{} {}", s.name, last),
+                position: Point::new(plot.x + 8.0, plot.y + index as f32 * TEXT_SIZE),
+                size: TEXT_SIZE,
+                color,
+                ..Default::default()
+            });
+        }
+        vec![frame.into_geometry()]
+    }
+}
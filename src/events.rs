@@ -0,0 +1,49 @@
+use iced::widget::{button, column, container, row, text};
+use iced_native::Length;
+
+use crate::{css, widget, GameState, Message, ViewState};
+use widget::Element;
+
+pub(crate) use sim::events::*;
+
+const TEXT_SIZE: u16 = 16;
+// entries shown in the log panel at once
+const LOG_LINES: usize = 8;
+
+// newest first
+pub(crate) fn view<'a>(g: &GameState, vs: &ViewState) -> Element<'a, Message> {
+    let mut lines = vec![crate::Element::from(
+        row![
+            text(format!("Events ({})", g.events.len()))
+                .size(TEXT_SIZE)
+                .width(Length::Fill),
+            button(text("Older").size(TEXT_SIZE)).on_press(Message::ScrollLog(true)),
+            button(text("Newer").size(TEXT_SIZE)).on_press(Message::ScrollLog(false)),
+        ]
+        .spacing(2),
+    )];
+    for e in g.events.iter().rev().skip(vs.log_offset).take(LOG_LINES) {
+        let p = pos(e.event);
+        lines.push(crate::Element::from(
+            button(text(format!("{}: {} at {}, {}", e.turn, e.event, p.x, p.y)).size(TEXT_SIZE))
+                .width(Length::Fill)
+                .on_press(Message::Locate(p)),
+        ));
+    }
+    crate::Element::from(
+        container(column(lines).spacing(2))
+            .width(Length::Fill)
+            .padding(4)
+            .style(css::Container::Bordered),
+    )
+}
+
+// the oldest entry always stays on screen
+pub(crate) fn scroll(older: bool, vs: &mut ViewState, g: &GameState) {
+    let offset = vs.log_offset;
+    vs.log_offset = if older {
+        (offset + LOG_LINES).min(g.events.len().saturating_sub(1))
+    } else {
+        offset.saturating_sub(LOG_LINES)
+    };
+}
@@ -0,0 +1,423 @@
+use std::collections::HashSet;
+
+use iced::{
+    alignment::{Horizontal, Vertical},
+    mouse,
+    widget::canvas::{self, event, Canvas, Cursor, Event, Frame, Geometry, Path, Stroke, Text},
+    widget::image,
+    Color, Point, Rectangle, Vector,
+};
+use iced_native::{layout, renderer, widget::Tree, Clipboard, Layout, Length, Shell, Widget};
+
+use crate::{
+    actionmachine, build_queue,
+    building::Blocker,
+    celldata::{self, CellState, CellStateData},
+    css, hexgrid, road_tool, visualize_cell, widget, GameState, Message, ViewState,
+};
+use widget::Element;
+
+// below this the labels are too small to read, so they are skipped
+const MIN_TEXT_SIZE: f32 = 8.0;
+// below this cells are only filled with their colour, their images wouldn't show anything
+const MIN_ART_SIZE: f32 = 16.0;
+const BLUEPRINT_COLOR: Color = Color {
+    r: 0.6,
+    g: 0.8,
+    b: 1.0,
+    a: 1.0,
+};
+// blueprints no hub reaches, they won't start until one does
+const BLOCKED_COLOR: Color = Color {
+    r: 1.0,
+    g: 0.4,
+    b: 0.3,
+    a: 1.0,
+};
+// how much of the building a blueprint's ghost shows over the cell below
+const GHOST_ALPHA: f32 = 0.5;
+
+// The board drawn as real hexagons,
+// positions on screen are world pixels (hexgrid::to_pixel) minus the camera position
+struct HexView<'a> {
+    g: &'a GameState,
+    vs: &'a ViewState,
+    colors: &'a render::map::ColorCache,
+    layer: Layer,
+}
+
+// the canvas can't draw images, so the cell images go between two canvases
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Layer {
+    // hex fills
+    Tiles,
+    // outlines, blueprints and labels, this one also handles input
+    Overlay,
+}
+
+pub(crate) fn view<'a>(
+    g: &'a GameState,
+    vs: &'a ViewState,
+    colors: &'a render::map::ColorCache,
+    send: &std::sync::mpsc::Sender<CellState>,
+) -> Element<'a, Message> {
+    let canvas = |layer| {
+        crate::Element::from(
+            Canvas::new(HexView {
+                g,
+                vs,
+                colors,
+                layer,
+            })
+            .width(Length::Fill)
+            .height(Length::Fill),
+        )
+    };
+    crate::Element::from(Board {
+        tiles: canvas(Layer::Tiles),
+        overlay: canvas(Layer::Overlay),
+        art: art(g, vs, send),
+    })
+}
+
+// images of the cells in view, inside their hex and relative to the top left of the board.
+// Missing ones are asked for and show up once they are made
+fn art(
+    g: &GameState,
+    vs: &ViewState,
+    send: &std::sync::mpsc::Sender<CellState>,
+) -> Vec<(image::Handle, Rectangle)> {
+    if vs.hex_size < MIN_ART_SIZE {
+        return Vec::new();
+    }
+    // same aspect as in the inspector, small enough to stay clear of the hex outline
+    let height = vs.hex_size;
+    let width = height * visualize_cell::START_CELL_Y_SIZE / visualize_cell::START_CELL_X_SIZE;
+    let mut missing = HashSet::new();
+    let mut ret = Vec::new();
+    let cells = hexgrid::view_port(&g.matrix, vs.top_left_hex, vs.view_cells_x, vs.view_cells_y);
+    for (_, column) in cells {
+        for (p, c) in column {
+            match vs.img_buffer.get(&c) {
+                Some(handle) => {
+                    let center = to_screen(p, vs);
+                    let bounds = Rectangle {
+                        x: center.x - width / 2.0,
+                        y: center.y - height / 2.0,
+                        width,
+                        height,
+                    };
+                    ret.push((handle.clone(), bounds));
+                }
+                None => {
+                    missing.insert(c);
+                }
+            }
+        }
+    }
+    for c in missing {
+        let _ = send.send(c);
+    }
+    ret
+}
+
+fn has_art(c: CellState, vs: &ViewState) -> bool {
+    vs.hex_size >= MIN_ART_SIZE && vs.img_buffer.contains_key(&c)
+}
+
+pub(crate) fn to_screen(p: hexgrid::Pos, vs: &ViewState) -> Point {
+    let (x, y) = hexgrid::to_pixel(p, vs.hex_size);
+    Point::new(x - vs.top_left_pos.x, y - vs.top_left_pos.y)
+}
+
+pub(crate) fn from_screen(p: Point, vs: &ViewState) -> hexgrid::Pos {
+    hexgrid::from_pixel(
+        (p.x + vs.top_left_pos.x, p.y + vs.top_left_pos.y),
+        vs.hex_size,
+    )
+}
+
+fn hex_path(size: f32) -> Path {
+    Path::new(|b| {
+        for i in 0..6 {
+            let angle = std::f32::consts::PI / 3.0 * i as f32;
+            let corner = Point::new(size * angle.cos(), size * angle.sin());
+            if i == 0 {
+                b.move_to(corner)
+            } else {
+                b.line_to(corner)
+            }
+        }
+        b.close()
+    })
+}
+
+fn label(c: CellState) -> String {
+    match c.data {
+        CellStateData::InProgress(actionmachine::InProgress::Pure(n))
+        | CellStateData::InProgress(actionmachine::InProgress::WithOther(n, _)) => {
+            format!("{} {}", c.variant, n)
+        }
+        _ => c.variant.to_string(),
+    }
+}
+
+impl canvas::Program<Message, css::Theme> for HexView<'_> {
+    // where the current road drag started
+    type State = Option<hexgrid::Pos>;
+
+    fn update(
+        &self,
+        state: &mut Self::State,
+        event: Event,
+        bounds: Rectangle,
+        cursor: Cursor,
+    ) -> (event::Status, Option<Message>) {
+        let vs = self.vs;
+        if self.layer == Layer::Tiles {
+            return (event::Status::Ignored, None);
+        }
+        match (event, cursor.position_in(&bounds)) {
+            // captured, so dragging out a road doesn't also move the map
+            (Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)), Some(p))
+                if vs.road_tool =>
+            {
+                let start = from_screen(p, vs);
+                *state = Some(start);
+                (
+                    event::Status::Captured,
+                    Some(Message::RoadPreview(start, start)),
+                )
+            }
+            (Event::Mouse(mouse::Event::CursorMoved { .. }), Some(p)) if state.is_some() => {
+                let (Some(start), end) = (*state, from_screen(p, vs)) else {
+                    unreachable!()
+                };
+                if vs.road_preview == Some((start, end)) {
+                    (event::Status::Captured, None)
+                } else {
+                    (
+                        event::Status::Captured,
+                        Some(Message::RoadPreview(start, end)),
+                    )
+                }
+            }
+            (Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)), _)
+                if state.is_some() =>
+            {
+                *state = None;
+                (event::Status::Captured, None)
+            }
+            // not captured, a press is also the start of dragging the map around
+            (Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)), Some(p)) => (
+                event::Status::Ignored,
+                Some(Message::Select(from_screen(p, vs))),
+            ),
+            _ => (event::Status::Ignored, None),
+        }
+    }
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        _theme: &css::Theme,
+        bounds: Rectangle,
+        _cursor: Cursor,
+    ) -> Vec<Geometry> {
+        let vs = self.vs;
+        let mut frame = Frame::new(bounds.size());
+        let hex = hex_path(vs.hex_size);
+        // blueprints are drawn as a smaller outline inside the cell they are placed on
+        let ghost = hex_path(vs.hex_size * 0.75);
+        let text_size = vs.hex_size / 3.0;
+        let road: HashSet<hexgrid::Pos> = match vs.road_preview {
+            Some((from, to)) => road_tool::plan(from, to, self.g).into_iter().collect(),
+            None => HashSet::new(),
+        };
+        let queued = build_queue::by_pos(self.g);
+        let cells = hexgrid::view_port(
+            &self.g.matrix,
+            vs.top_left_hex,
+            vs.view_cells_x,
+            vs.view_cells_y,
+        );
+        for (_, column) in cells {
+            for (p, c) in column {
+                let center = to_screen(p, vs);
+                let (r, g, b) = render::map::cell_color(c, self.colors);
+                frame.with_save(|frame| {
+                    frame.translate(Vector::new(center.x, center.y));
+                    if self.layer == Layer::Tiles {
+                        frame.fill(&hex, Color::from_rgb(r as f32, g as f32, b as f32));
+                        return;
+                    }
+                    frame.stroke(&hex, Stroke::default().with_color(Color::BLACK));
+                    if vs.selected == Some(p) {
+                        frame.stroke(
+                            &hex,
+                            Stroke::default().with_color(Color::WHITE).with_width(3.0),
+                        );
+                    }
+                    if road.contains(&p) {
+                        frame.fill(&ghost, BLUEPRINT_COLOR);
+                    }
+                    if let Some((place, b)) = queued.get(&p) {
+                        let color = match b.blocker {
+                            Some(Blocker::WrongCell | Blocker::NotConnected) => BLOCKED_COLOR,
+                            _ => BLUEPRINT_COLOR,
+                        };
+                        // the building to be, see-through over what is there now
+                        let (red, green, blue) =
+                            render::map::cell_color(celldata::unit_state(b.cv), self.colors);
+                        let fill =
+                            Color::from_rgba(red as f32, green as f32, blue as f32, GHOST_ALPHA);
+                        frame.fill(&ghost, fill);
+                        frame.stroke(&ghost, Stroke::default().with_color(color).with_width(2.0));
+                        if text_size >= MIN_TEXT_SIZE {
+                            frame.fill_text(Text {
+                                content: format!("{}. {}", place, b.cv),
+                                position: Point::new(0.0, text_size * 1.2),
+                                size: text_size,
+                                color,
+                                horizontal_alignment: Horizontal::Center,
+                                vertical_alignment: Vertical::Center,
+                                ..Default::default()
+                            });
+                        }
+                    }
+                    // the image already shows what the cell is
+                    if text_size >= MIN_TEXT_SIZE && !has_art(c, vs) {
+                        frame.fill_text(Text {
+                            content: label(c),
+                            position: Point::ORIGIN,
+                            size: text_size,
+                            color: Color::BLACK,
+                            horizontal_alignment: Horizontal::Center,
+                            vertical_alignment: Vertical::Center,
+                            ..Default::default()
+                        });
+                    }
+                });
+            }
+        }
+        vec![frame.into_geometry()]
+    }
+}
+
+// the two canvas layers with the cell images drawn in between
+struct Board<'a> {
+    tiles: Element<'a, Message>,
+    overlay: Element<'a, Message>,
+    art: Vec<(image::Handle, Rectangle)>,
+}
+
+impl<'a> Widget<Message, widget::Renderer> for Board<'a> {
+    fn width(&self) -> Length {
+        Length::Fill
+    }
+
+    fn height(&self) -> Length {
+        Length::Fill
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.tiles), Tree::new(&self.overlay)]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(&[&self.tiles, &self.overlay]);
+    }
+
+    fn layout(&self, renderer: &widget::Renderer, limits: &layout::Limits) -> layout::Node {
+        self.overlay.as_widget().layout(renderer, limits)
+    }
+
+    fn draw(
+        &self,
+        state: &Tree,
+        renderer: &mut widget::Renderer,
+        theme: &css::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+    ) {
+        self.tiles.as_widget().draw(
+            &state.children[0],
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor_position,
+            viewport,
+        );
+        let bounds = layout.bounds();
+        // cells cut by the edge of the board mustn't spill onto the side panel
+        iced_native::Renderer::with_layer(renderer, bounds, |renderer| {
+            for (handle, at) in &self.art {
+                iced_native::image::Renderer::draw(
+                    renderer,
+                    handle.clone(),
+                    Rectangle {
+                        x: bounds.x + at.x,
+                        y: bounds.y + at.y,
+                        ..*at
+                    },
+                );
+            }
+        });
+        self.overlay.as_widget().draw(
+            &state.children[1],
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor_position,
+            viewport,
+        );
+    }
+
+    fn on_event(
+        &mut self,
+        state: &mut Tree,
+        event: iced_native::Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &widget::Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) -> event::Status {
+        self.overlay.as_widget_mut().on_event(
+            &mut state.children[1],
+            event,
+            layout,
+            cursor_position,
+            renderer,
+            clipboard,
+            shell,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        state: &Tree,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+        renderer: &widget::Renderer,
+    ) -> mouse::Interaction {
+        self.overlay.as_widget().mouse_interaction(
+            &state.children[1],
+            layout,
+            cursor_position,
+            viewport,
+            renderer,
+        )
+    }
+}
+
+impl<'a> From<Board<'a>> for Element<'a, Message> {
+    fn from(board: Board<'a>) -> Self {
+        Element::new(board)
+    }
+}
@@ -0,0 +1,167 @@
+use std::collections::{HashMap, HashSet};
+
+use iced::widget::{column, container, image, text};
+use iced_native::Length;
+
+use crate::{
+    celldata::{CellState, CellStateData, CellStateVariant},
+    components, css,
+    hexgrid::{self, Pos},
+    resource::{self, Resource, ResourceStockpile, ResourceType},
+    widget, GameState, Message,
+};
+use widget::Element;
+
+const ICON_SIZE: f32 = 24.0;
+const TEXT_SIZE: u16 = 18;
+
+// Hubs joined by roads share their stockpiles, so they are shown together
+#[derive(Debug, Clone)]
+pub(crate) struct Network {
+    hubs: Vec<Pos>,
+    stock: ResourceStockpile,
+}
+
+// Walks every hub network, so it is only refreshed when the game state changes
+#[derive(Debug, Clone)]
+pub(crate) struct Hud {
+    total: ResourceStockpile,
+    networks: Vec<Network>,
+}
+
+pub(crate) type Icons = HashMap<ResourceType, image::Handle>;
+
+pub(crate) fn new() -> Hud {
+    Hud {
+        total: resource::sum_stockpiles([]),
+        networks: vec![],
+    }
+}
+
+fn hub_stockpile(c: CellState) -> Option<ResourceStockpile> {
+    match c {
+        CellState {
+            variant: CellStateVariant::Hub,
+            data: CellStateData::Resource(Resource::Pure(r)),
+        } => Some(r),
+        _ => None,
+    }
+}
+
+// takes g mutably only to fill in the component index
+pub(crate) fn refresh(g: &mut GameState) -> Hud {
+    let mut hubs: Vec<Pos> = components::buildings(CellStateVariant::Hub, g).collect();
+    hubs.sort_by_key(|p| (p.x, p.y));
+    let mut seen = HashSet::new();
+    let mut networks = vec![];
+    for h in hubs {
+        if seen.contains(&h) {
+            continue;
+        }
+        let members = connected_hubs(h, g);
+        seen.extend(members.iter().cloned());
+        let stock = resource::sum_stockpiles(
+            members
+                .iter()
+                .filter_map(|p| hub_stockpile(hexgrid::unsafe_get(*p, &g.matrix))),
+        );
+        networks.push(Network {
+            hubs: members,
+            stock,
+        });
+    }
+    Hud {
+        total: resource::sum_stockpiles(networks.iter().map(|n| n.stock)),
+        networks,
+    }
+}
+
+// every hub reachable from start over hubs and roads, start included
+fn connected_hubs(start: Pos, g: &mut GameState) -> Vec<Pos> {
+    let mut ret: Vec<Pos> = components::connected(components::Kind::Logistics, start, g)
+        .into_iter()
+        .filter(|p| hub_stockpile(hexgrid::unsafe_get(*p, &g.matrix)).is_some())
+        .collect();
+    ret.sort_by_key(|p| (p.x, p.y));
+    ret
+}
+
+// change in the summed hub stock over the last turn
+fn deltas(g: &GameState) -> resource::PacketMap {
+    let mut latest = g.stats.iter().rev();
+    let (Some(now), Some(before)) = (latest.next(), latest.next()) else {
+        return HashMap::new();
+    };
+    enum_iterator::all::<ResourceType>()
+        .map(|t| {
+            let get = |m: &resource::PacketMap| *m.get(&t).unwrap_or(&0);
+            (t, get(&now.hub_resources) - get(&before.hub_resources))
+        })
+        .collect()
+}
+
+pub(crate) fn view<'a>(hud: &Hud, g: &GameState, icons: &Icons) -> Element<'a, Message> {
+    let deltas = deltas(g);
+    let mut total = vec![crate::Element::from(text("All hubs").size(TEXT_SIZE))];
+    total.extend(stock_row(hud.total, icons, Some(&deltas)));
+    let r = g.resources;
+    total.push(crate::Element::from(
+        text(format!(
+            "Hot tiles {}, leak {}, heat efficency {:.2}",
+            r.tiles, r.leak, r.heat_efficency
+        ))
+        .size(TEXT_SIZE),
+    ));
+    let mut lines = vec![crate::Element::from(
+        iced::widget::Row::with_children(total).spacing(8),
+    )];
+    // a single network is the same as the total
+    if hud.networks.len() > 1 {
+        for n in hud.networks.iter() {
+            let first = n.hubs[0];
+            let mut row = vec![crate::Element::from(
+                text(format!(
+                    "{} hub(s) at {}, {}",
+                    n.hubs.len(),
+                    first.x,
+                    first.y
+                ))
+                .size(TEXT_SIZE),
+            )];
+            row.extend(stock_row(n.stock, icons, None));
+            lines.push(crate::Element::from(
+                iced::widget::Row::with_children(row).spacing(8),
+            ));
+        }
+    }
+    crate::Element::from(
+        container(column(lines).spacing(4))
+            .width(Length::Fill)
+            .padding(4)
+            .style(css::Container::Bordered),
+    )
+}
+
+fn stock_row<'a>(
+    r: ResourceStockpile,
+    icons: &Icons,
+    deltas: Option<&resource::PacketMap>,
+) -> Vec<Element<'a, Message>> {
+    enum_iterator::all::<ResourceType>()
+        .map(|t| (t, r[t as usize]))
+        .filter(|(_, d)| d.max > 0)
+        .map(|(t, d)| {
+            let label = match icons.get(&t) {
+                Some(handle) => {
+                    crate::Element::from(image(handle.clone()).width(ICON_SIZE).height(ICON_SIZE))
+                }
+                None => crate::Element::from(text(format!("{:?}", t)).size(TEXT_SIZE)),
+            };
+            let amount = match deltas.and_then(|m| m.get(&t)) {
+                Some(delta) => format!("{}/{} ({:+})", d.current, d.max, delta),
+                None => format!("{}/{}", d.current, d.max),
+            };
+            crate::Element::from(iced::widget::row![label, text(amount).size(TEXT_SIZE)].spacing(2))
+        })
+        .collect()
+}
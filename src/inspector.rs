@@ -0,0 +1,160 @@
+use iced::widget::{column, container, text};
+use iced_native::Length;
+
+use crate::{
+    actionmachine::{self, InProgress, Other},
+    build_queue,
+    celldata::{CellState, CellStateData, Slot},
+    css, hexgrid,
+    logistics_plane::{self, LogisticsState},
+    resource::{self, Resource},
+    visualize_cell, widget, GameState, Message, ViewState,
+};
+use widget::Element;
+
+pub(crate) const INSPECTOR_WIDTH: f32 = 300.0;
+const TEXT_SIZE: u16 = 16;
+
+// Side panel describing the selected cell,
+// the cell itself (image and build buttons) on top and everything known about it below
+pub(crate) fn view<'a>(
+    g: &GameState,
+    vs: &ViewState,
+    send: &std::sync::mpsc::Sender<CellState>,
+) -> Element<'a, Message> {
+    let content = match vs.selected {
+        Some(p) => {
+            let c = hexgrid::unsafe_get(p, &g.matrix);
+            let details = describe(p, c, g)
+                .into_iter()
+                .map(|line| crate::Element::from(text(line).size(TEXT_SIZE)))
+                .collect();
+            crate::Element::from(column![
+                visualize_cell::to_gui(p, c, g, vs, send),
+                iced::widget::Column::with_children(details).spacing(2),
+            ])
+        }
+        None => crate::Element::from(text("Click a cell to inspect it").size(TEXT_SIZE)),
+    };
+    crate::Element::from(
+        container(content)
+            .width(INSPECTOR_WIDTH)
+            .height(Length::Fill)
+            .padding(10)
+            .style(css::Container::Bordered),
+    )
+}
+
+fn describe(p: hexgrid::Pos, c: CellState, g: &GameState) -> Vec<String> {
+    let mut lines = vec![
+        format!("Position: {}, {}", p.x, p.y),
+        format!("Variant: {}", c.variant),
+    ];
+    lines.extend(describe_data(c));
+    lines.push(format!(
+        "Logistics: {}",
+        describe_logistics(hexgrid::unsafe_get(p, &g.logistics_plane))
+    ));
+    lines.push(describe_action(p, c, g));
+    if let Some((place, b)) = build_queue::queued_at(p, g) {
+        lines.push(format!(
+            "Blueprint: {}, number {} in the queue",
+            b.cv, place
+        ));
+        if let Some(blocker) = b.blocker {
+            lines.push(format!("Blueprint {}", blocker));
+        }
+    }
+    let hubs = serving_hubs(p, g);
+    if hubs.is_empty() {
+        lines.push("Served by: no hub".to_string());
+    } else {
+        lines.push("Served by:".to_string());
+        for h in hubs {
+            lines.push(format!(
+                "  hub at {}, {} ({} away)",
+                h.x,
+                h.y,
+                hexgrid::distance(p, h)
+            ));
+        }
+    }
+    lines
+}
+
+fn describe_data(c: CellState) -> Vec<String> {
+    match c.data {
+        CellStateData::Unit => vec![],
+        CellStateData::Slot { slot: Slot::Empty } => vec!["Slot: empty".to_string()],
+        CellStateData::Slot { slot: Slot::Done } => vec!["Slot: done".to_string()],
+        CellStateData::InProgress(InProgress::Pure(n)) => {
+            vec![format!("In progress: {} turns left", n)]
+        }
+        CellStateData::InProgress(InProgress::WithOther(n, oth)) => vec![
+            format!("In progress: {} turns left", n),
+            format!("Becomes: {}", describe_other(oth)),
+        ],
+        CellStateData::Resource(Resource::Pure(r)) => describe_stockpile(c, r),
+        CellStateData::Resource(Resource::WithVariant(r, cv)) => {
+            let mut lines = vec![format!("Becomes: {}", cv)];
+            lines.extend(describe_stockpile(c, r));
+            lines
+        }
+    }
+}
+
+fn describe_other(oth: Other) -> String {
+    match oth {
+        Other::CellStateVariant(cv) => cv.to_string(),
+        Other::CvAndRS(cv, _) => format!("{} (keeps resources)", cv),
+    }
+}
+
+fn describe_stockpile(c: CellState, r: resource::ResourceStockpile) -> Vec<String> {
+    let rows = resource::to_display_rows(c.variant, r);
+    if rows.is_empty() {
+        return vec!["Stockpile: empty".to_string()];
+    }
+    let mut lines = vec!["Stockpile:".to_string()];
+    for (t, current, max) in rows {
+        lines.push(format!("  {:?}: {}/{}", t, current, max));
+    }
+    lines
+}
+
+fn describe_logistics(l: LogisticsState) -> String {
+    match l {
+        LogisticsState::None => "not connected".to_string(),
+        LogisticsState::Source => "hub".to_string(),
+        LogisticsState::Available(a) => {
+            let borrowed: i32 = a
+                .borrows
+                .values()
+                .map(|b| b[resource::ResourceType::Builders as usize].abs())
+                .sum();
+            let taken: i32 = a.taken_lp.values().sum();
+            format!(
+                "in range of {} hub(s), {} builder(s) borrowed, {} lp taken",
+                a.locations.len(),
+                borrowed,
+                taken
+            )
+        }
+    }
+}
+
+fn describe_action(p: hexgrid::Pos, c: CellState, g: &GameState) -> String {
+    match actionmachine::prio(c.variant) {
+        Some(prio) if g.action_machine[prio].contains(&p) => {
+            format!("Action machine: priority {}", prio)
+        }
+        Some(prio) => format!("Action machine: priority {}, not scheduled", prio),
+        None => "Action machine: no actions".to_string(),
+    }
+}
+
+fn serving_hubs(p: hexgrid::Pos, g: &GameState) -> Vec<hexgrid::Pos> {
+    let mut hubs = logistics_plane::connected_sources(p, g);
+    hubs.sort_by_key(|h| (hexgrid::distance(p, *h), h.x, h.y));
+    hubs
+}
@@ -0,0 +1,97 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use iced::keyboard::KeyCode;
+use serde::Deserialize;
+
+use crate::{hexgrid, menu, GameState, Message, ViewState};
+
+pub(crate) const KEYBINDINGS_FILE: &str = "keybindings.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub(crate) enum Action {
+    PanUp,
+    PanDown,
+    PanLeft,
+    PanRight,
+    ZoomIn,
+    ZoomOut,
+    EndTurn,
+    ToggleBlueprints,
+    ToggleRoadTool,
+    // n-th (starting at 1) option of the build menu of the selected cell
+    Hotkey(usize),
+}
+
+// keys are the names of iced::keyboard::KeyCode variants, like "W", "Left" or "Key1"
+pub(crate) type Keybindings = HashMap<String, Action>;
+
+pub(crate) fn defaults() -> Keybindings {
+    let mut ret = HashMap::from([
+        ("W", Action::PanUp),
+        ("Up", Action::PanUp),
+        ("S", Action::PanDown),
+        ("Down", Action::PanDown),
+        ("A", Action::PanLeft),
+        ("Left", Action::PanLeft),
+        ("D", Action::PanRight),
+        ("Right", Action::PanRight),
+        ("Plus", Action::ZoomIn),
+        ("Equals", Action::ZoomIn),
+        ("NumpadAdd", Action::ZoomIn),
+        ("Minus", Action::ZoomOut),
+        ("NumpadSubtract", Action::ZoomOut),
+        ("Space", Action::EndTurn),
+        ("B", Action::ToggleBlueprints),
+        ("R", Action::ToggleRoadTool),
+    ]);
+    let numbers = [
+        "Key1", "Key2", "Key3", "Key4", "Key5", "Key6", "Key7", "Key8", "Key9",
+    ];
+    for (i, k) in numbers.into_iter().enumerate() {
+        ret.insert(k, Action::Hotkey(i + 1));
+    }
+    ret.into_iter().map(|(k, a)| (k.to_string(), a)).collect()
+}
+
+// Bindings in the file replace the default for that key, keys not in the file keep their default.
+// A missing file is fine, a broken one is reported and ignored
+pub(crate) fn load(path: &Path) -> Keybindings {
+    let mut ret = defaults();
+    let Ok(s) = fs::read_to_string(path) else {
+        return ret;
+    };
+    match serde_json::from_str::<Keybindings>(&s) {
+        Ok(from_file) => ret.extend(from_file),
+        Err(e) => tracing::warn!("Error reading {:?}, using default keybindings: {}", path, e),
+    }
+    ret
+}
+
+pub(crate) fn to_message(
+    k: &Keybindings,
+    key_code: KeyCode,
+    g: &GameState,
+    vs: &ViewState,
+) -> Option<Message> {
+    let action = k.get(&format!("{:?}", key_code))?;
+    // about one hex per key press, whatever the zoom level
+    let step = vs.hex_size * 2.0;
+    match action {
+        Action::PanUp => Some(Message::Pan(iced::Vector::new(0.0, -step))),
+        Action::PanDown => Some(Message::Pan(iced::Vector::new(0.0, step))),
+        Action::PanLeft => Some(Message::Pan(iced::Vector::new(-step, 0.0))),
+        Action::PanRight => Some(Message::Pan(iced::Vector::new(step, 0.0))),
+        Action::ZoomIn => Some(Message::Zoom(false)),
+        Action::ZoomOut => Some(Message::Zoom(true)),
+        Action::EndTurn => Some(Message::EndTurn),
+        Action::ToggleBlueprints => Some(Message::ToggleBlueprintMode),
+        Action::ToggleRoadTool => Some(Message::ToggleRoadTool),
+        Action::Hotkey(n) => {
+            let pos = vs.selected?;
+            let c = hexgrid::unsafe_get(pos, &g.matrix);
+            let actions = menu::available_actions(pos, c, g, vs)?;
+            let cv = actions.get(n.checked_sub(1)?)?;
+            Some(Message::Build(*cv, pos))
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use std::{env, fs::File, sync::Mutex};
+
+use tracing_subscriber::{fmt, fmt::format::FmtSpan, prelude::*, EnvFilter};
+
+// Levels per module, same syntax as RUST_LOG, e.g.
+// GAME_LOG=info,sim::actionmachine=debug,render=trace
+pub(crate) const FILTER_VAR: &str = "GAME_LOG";
+// if set everything that passes the filter is also written to this file
+pub(crate) const FILE_VAR: &str = "GAME_LOG_FILE";
+const DEFAULT_FILTER: &str = "warn,rust_game_1=info,sim=info,render=info";
+
+// Spans are reported when they close, together with how long they took
+pub(crate) fn init() {
+    let filter =
+        EnvFilter::try_from_env(FILTER_VAR).unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+    let stderr = fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_span_events(FmtSpan::CLOSE);
+    let file = match env::var(FILE_VAR) {
+        Ok(path) => match File::create(&path) {
+            Ok(f) => Some(
+                fmt::layer()
+                    .with_ansi(false)
+                    .with_writer(Mutex::new(f))
+                    .with_span_events(FmtSpan::CLOSE),
+            ),
+            Err(e) => {
+                eprintln!("Error creating log file {:?}: {:?}", path, e);
+                None
+            }
+        },
+        Err(_) => None,
+    };
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(stderr)
+        .with(file)
+        .init();
+}
@@ -1,15 +1,22 @@
-pub(crate) mod actionmachine;
-pub(crate) mod building;
-pub(crate) mod celldata;
+pub(crate) mod build_queue;
+pub(crate) mod charts;
 pub(crate) mod css;
-pub(crate) mod hexgrid;
-pub(crate) mod logistics_plane;
-pub(crate) mod make_imgs;
-pub(crate) mod make_world;
+pub(crate) mod events;
+pub(crate) mod hexview;
+pub(crate) mod hud;
+pub(crate) mod inspector;
+pub(crate) mod keybindings;
+pub(crate) mod logging;
 pub(crate) mod menu;
-pub(crate) mod resource;
+pub(crate) mod minimap;
+pub(crate) mod road_tool;
 pub(crate) mod visualize_cell;
 
+use sim::{
+    actionmachine, building, celldata, components, error, hexgrid, logistics_plane, make_world,
+    new_game, resource, stats, validate, GameState,
+};
+
 use iced::executor;
 use iced::widget::{button, container};
 use iced::{Application, Command, Length, Settings};
@@ -27,35 +34,237 @@ mod widget {
 }
 
 use std::cell::RefCell;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::mpsc::{self, Receiver, Sender};
-use std::{dbg, env, fs, vec};
+use std::{env, fs, vec};
 
 pub(crate) fn main() {
+    logging::init();
     let args: Vec<String> = env::args().collect();
     if args.len() == 1 {
         let _ = AppState::run(Settings {
             ..Settings::default()
         });
     } else if args[1] == "test" {
-        dbg!(args);
+        tracing::info!("{:?}", args);
+    } else if args[1] == "export-map" {
+        export_map(&args[2..]);
+    } else if args[1] == "simulate" {
+        simulate(&args[2..]);
+    } else if args[1] == "bench-turns" {
+        bench_turns(&args[2..]);
     }
 }
 
-#[derive(Clone)]
-pub(crate) struct GameState {
-    matrix: hexgrid::Board,
-    logistics_plane: logistics_plane::LogisticsPlane,
-    resources: GameResources,
-    action_machine: actionmachine::ActionMachine,
-    img_buffer: visualize_cell::ImgBuffer,
-    io_cache: IOCache,
+// export-map <out.png> [seed] [radius in chunks] [--logistics]
+// there are no save files yet, the map is always generated from a seed
+fn export_map(args: &[String]) {
+    let with_logistics = args.iter().any(|i| i == "--logistics");
+    let positional: Vec<&String> = args.iter().filter(|i| !i.starts_with("--")).collect();
+    let Some(out) = positional.get(0) else {
+        println!("usage: export-map <out.png> [seed] [radius] [--logistics]");
+        return;
+    };
+    let matrix = match positional.get(1).map(|i| i.parse::<u32>()) {
+        Some(Ok(seed)) => make_world::new_with_seed(seed),
+        Some(Err(e)) => {
+            println!("bad seed: {:?}", e);
+            return;
+        }
+        None => make_world::new(),
+    };
+    let radius = match positional.get(2).map(|i| i.parse::<i32>()) {
+        Some(Ok(r)) => r,
+        Some(Err(e)) => {
+            println!("bad radius: {:?}", e);
+            return;
+        }
+        None => 1,
+    };
+    let mut g = new_game(matrix);
+    let chunk = hexgrid::CHUNK_SIZE as i32;
+    let extra = (2 * radius * chunk - 1).max(0);
+    hexgrid::touch_all_chunks(
+        &mut g.matrix,
+        hexgrid::XYCont {
+            x: -radius * chunk,
+            y: -radius * chunk,
+        },
+        extra,
+        extra,
+    );
+    match render::map::export_map(&g, &PathBuf::from(out), with_logistics) {
+        Ok(_) => println!("wrote {:?}", out),
+        Err(e) => println!("Error writing {:?}: {}", out, e),
+    }
+}
+
+// simulate <turns> <out.csv> [seed] [events.csv]
+// runs turns without any player input and writes the statistics of every turn,
+// and if given every game event
+fn simulate(args: &[String]) {
+    let (Some(Ok(turns)), Some(out)) = (args.get(0).map(|i| i.parse::<u32>()), args.get(1)) else {
+        println!("usage: simulate <turns> <out.csv> [seed] [events.csv]");
+        return;
+    };
+    let matrix = match args.get(2).map(|i| i.parse::<u32>()) {
+        Some(Ok(seed)) => make_world::new_with_seed(seed),
+        Some(Err(e)) => {
+            println!("bad seed: {:?}", e);
+            return;
+        }
+        None => make_world::new(),
+    };
+    let mut g = new_game(matrix);
+    for _ in 0..turns {
+        match actionmachine::run(g.clone()) {
+            Ok(g1) => g = g1,
+            Err(e) => {
+                println!("turn {} failed: {}", g.turn, e);
+                break;
+            }
+        }
+        if validate::enabled() && !validate::report(&g) {
+            println!("turn {} failed validation", g.turn);
+            break;
+        }
+    }
+    match stats::write_csv(&g.stats, &PathBuf::from(out)) {
+        Ok(_) => println!("wrote {} turns to {:?}", g.turn, out),
+        Err(e) => println!("Error writing {:?}: {:?}", out, e),
+    }
+    if let Some(events_out) = args.get(3) {
+        match events::write_csv(&g.events, &PathBuf::from(events_out)) {
+            Ok(_) => println!("wrote {} events to {:?}", g.events.len(), events_out),
+            Err(e) => println!("Error writing {:?}: {:?}", events_out, e),
+        }
+    }
+}
+
+// bench-turns <max radius in chunks> [turns] [seed]
+// times cloning the game state and running a turn, like the UI does every turn,
+// for more and more explored map.
+// Recording the statistics walks every loaded cell, its share of the turn is listed separately
+fn bench_turns(args: &[String]) {
+    let Some(Ok(max_radius)) = args.get(0).map(|i| i.parse::<i32>()) else {
+        println!("usage: bench-turns <max radius> [turns] [seed]");
+        return;
+    };
+    let turns = match args.get(1).map(|i| i.parse::<u32>()) {
+        Some(Ok(t)) => t,
+        Some(Err(e)) => {
+            println!("bad turns: {:?}", e);
+            return;
+        }
+        None => 20,
+    };
+    let seed = match args.get(2).map(|i| i.parse::<u32>()) {
+        Some(Ok(seed)) => seed,
+        Some(Err(e)) => {
+            println!("bad seed: {:?}", e);
+            return;
+        }
+        None => 0,
+    };
+    println!("radius,chunks,clone_us,turn_us,stats_us");
+    for radius in 0..=max_radius {
+        let mut g = new_game(make_world::new_with_seed(seed));
+        let chunk = hexgrid::CHUNK_SIZE as i32;
+        let extra = (2 * radius * chunk - 1).max(0);
+        hexgrid::touch_all_chunks(
+            &mut g.matrix,
+            hexgrid::XYCont {
+                x: -radius * chunk,
+                y: -radius * chunk,
+            },
+            extra,
+            extra,
+        );
+        let chunks = hexgrid::loaded_chunks(&g.matrix).count();
+        let mut clone_time = std::time::Duration::ZERO;
+        let mut turn_time = std::time::Duration::ZERO;
+        let mut stats_time = std::time::Duration::ZERO;
+        for _ in 0..turns {
+            let start = std::time::Instant::now();
+            let g1 = g.clone();
+            clone_time = clone_time + start.elapsed();
+            let start = std::time::Instant::now();
+            // the same as actionmachine::run, timed in two parts
+            let g1 = match actionmachine::run_cells(g1) {
+                Ok(g1) => g1,
+                Err(e) => {
+                    println!("turn {} failed: {}", g.turn, e);
+                    return;
+                }
+            };
+            turn_time = turn_time + start.elapsed();
+            let start = std::time::Instant::now();
+            g = stats::record(g1);
+            stats_time = stats_time + start.elapsed();
+        }
+        let per_turn = |d: std::time::Duration| d.as_micros() / turns.max(1) as u128;
+        println!(
+            "{},{},{},{},{}",
+            radius,
+            chunks,
+            per_turn(clone_time),
+            per_turn(turn_time),
+            per_turn(stats_time)
+        );
+    }
 }
 
 pub(crate) struct AppState {
     game_state: GameState,
+    view_state: ViewState,
     queues: Queues,
+    minimap: minimap::MinimapCache,
+    colors: render::map::ColorCache,
+    keybindings: keybindings::Keybindings,
+    speed: Speed,
+    show_stats: bool,
+    hud: hud::Hud,
+    icons: hud::Icons,
+    // the last action the game refused, shown until dismissed
+    error: Option<error::GameError>,
+}
+
+// turns per second when turns advance on their own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Speed {
+    Paused,
+    Normal,
+    Double,
+    Quadruple,
+}
+
+const SPEEDS: [Speed; 4] = [
+    Speed::Paused,
+    Speed::Normal,
+    Speed::Double,
+    Speed::Quadruple,
+];
+
+impl std::fmt::Display for Speed {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Speed::Paused => write!(f, "Pause"),
+            Speed::Normal => write!(f, "1x"),
+            Speed::Double => write!(f, "2x"),
+            Speed::Quadruple => write!(f, "4x"),
+        }
+    }
+}
+
+fn turn_interval(s: Speed) -> Option<std::time::Duration> {
+    let millis = match s {
+        Speed::Paused => return None,
+        Speed::Normal => 1000,
+        Speed::Double => 500,
+        Speed::Quadruple => 250,
+    };
+    Some(std::time::Duration::from_millis(millis))
 }
 
 #[derive(Debug)]
@@ -64,34 +273,55 @@ pub(crate) struct Queues {
     get_img_done: RefCell<Option<Receiver<ImgDoneEvent>>>,
 }
 
+// everything about how the game is shown: camera, input and cell images.
+// Kept apart from GameState so cloning or saving the game never carries it along
 #[derive(Debug, Clone)]
-pub(crate) struct IOCache {
+pub(crate) struct ViewState {
     top_left_pos: iced_native::Point,
     latest_cursor: iced_native::Point,
     is_mousedown: bool,
     top_left_hex: hexgrid::XYCont<i32>,
     view_cells_x: i32,
     view_cells_y: i32,
-    cell_x_size: f32,
-    cell_y_size: f32,
+    // center to corner of a hex in pixels
+    hex_size: f32,
+    selected: Option<hexgrid::Pos>,
+    // build buttons place blueprints instead of building right away
+    blueprint_mode: bool,
+    // dragging on the map lays out roads instead of moving it
+    road_tool: bool,
+    road_preview: Option<(hexgrid::Pos, hexgrid::Pos)>,
+    // how many of the newest events the log panel is scrolled past
+    log_offset: usize,
     width_px: i32,
     height_px: i32,
-}
-
-#[derive(Debug, Clone, Copy)]
-pub(crate) struct GameResources {
-    tiles: i32,
-    leak: i32,
-    heat_efficency: f64,
+    img_buffer: visualize_cell::ImgBuffer,
 }
 
 #[derive(Debug, Clone)]
 pub(crate) enum Message {
     Build(celldata::CellStateVariant, hexgrid::Pos),
     EndTurn,
+    SetSpeed(Speed),
+    ShowStats(bool),
+    ToggleBlueprintMode,
+    MoveBlueprint(usize, bool),
+    RemoveBlueprint(usize),
+    ToggleRoadTool,
+    RoadPreview(hexgrid::Pos, hexgrid::Pos),
+    ConfirmRoad,
+    CancelRoad,
+    ExportStats,
+    DismissError,
+    ScrollLog(bool),
+    Locate(hexgrid::Pos),
     Zoom(bool),
+    Pan(iced::Vector),
+    MinimapJump(hexgrid::Pos),
+    Select(hexgrid::Pos),
     NativeEvent(iced_native::Event),
     ImgDone(ImgDoneEvent),
+    IconsDone(HashMap<resource::ResourceType, PathBuf>),
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -101,17 +331,18 @@ pub(crate) struct ImgDoneEvent {
 }
 
 fn read_reply_loop(
-    mut done: HashSet<ImgDoneEvent>,
+    mut done: HashSet<celldata::CellState>,
     rx: Receiver<celldata::CellState>,
     tx: Sender<ImgDoneEvent>,
 ) {
     loop {
         let data = rx.recv().unwrap();
-        let path = make_imgs::make_image(data);
-        let msg = ImgDoneEvent { path, data };
-        if done.insert(msg.clone()) {
-            tx.send(msg).unwrap();
+        // the board asks for every cell in view that has no image yet, on every redraw
+        if !done.insert(data) {
+            continue;
         }
+        let path = render::make_image(data);
+        tx.send(ImgDoneEvent { path, data }).unwrap();
     }
 }
 
@@ -133,68 +364,38 @@ impl Application for AppState {
                 (Message::ImgDone(first), receiver0)
             },
         );
-        subscription::Subscription::batch(vec![a, b])
+        let mut subs = vec![a, b];
+        if let Some(interval) = turn_interval(self.speed) {
+            subs.push(iced::time::every(interval).map(|_| Message::EndTurn));
+        }
+        subscription::Subscription::batch(subs)
     }
 
     fn new(_flags: ()) -> (Self, Command<Message>) {
         let (s1, r1) = mpsc::channel();
         let (s2, r2) = mpsc::channel();
         std::thread::spawn(move || read_reply_loop(HashSet::new(), r1, s2));
-        let start_x: i32 = 0;
-        let start_y: i32 = 0;
-        let start_view_cells_x = 7;
-        let start_view_cells_y = 5;
-        let start_cell_x_size = 100.0;
-        let start_cell_y_size = 125.0;
-        let width_px = 1000;
-        let height_px = 1000;
-        let m1 = make_world::new();
-        let mut g = GameState {
-            matrix: m1,
-            logistics_plane: logistics_plane::new_plane(),
-            resources: GameResources {
-                tiles: 0,
-                leak: 1,
-                heat_efficency: 0.0,
-            },
-            action_machine: actionmachine::new(),
-            img_buffer: visualize_cell::new_img_buffer(),
-            io_cache: IOCache {
-                top_left_pos: iced::Point {
-                    x: (start_x as f32 - (start_view_cells_x / 2) as f32) * start_cell_x_size,
-                    y: (start_y as f32 - (start_view_cells_y / 2) as f32) * start_cell_y_size,
-                },
-                latest_cursor: iced::Point { x: 0.0, y: 0.0 },
-                is_mousedown: false,
-                top_left_hex: hexgrid::XYCont {
-                    x: start_x as i32 - (start_view_cells_x / 2),
-                    y: start_y as i32 - (start_view_cells_y / 2),
-                },
-                view_cells_x: start_view_cells_x,
-                view_cells_y: start_view_cells_y,
-                cell_x_size: start_cell_x_size,
-                cell_y_size: start_cell_y_size,
-                width_px: width_px,
-                height_px: height_px,
-            },
-        };
-        let p = hexgrid::Pos {
-            x: start_x,
-            y: start_y,
-        };
-        let cv = celldata::CellStateVariant::Hub;
-        g = building::do_build(actionmachine::Other::CellStateVariant(cv), p, g);
-        let mut start_hub = hexgrid::get(p, &mut g.matrix);
-        start_hub = resource::add(resource::ResourceType::Wood, start_hub, 10).unwrap();
-        hexgrid::set(p, start_hub, &mut g.matrix);
-        let a = AppState {
+        let g = new_game(make_world::new());
+        let mut a = AppState {
             game_state: g,
+            view_state: new_view_state(),
             queues: Queues {
                 send_img_job: s1,
                 get_img_done: RefCell::new(Some(r2)),
             },
+            minimap: minimap::new(),
+            colors: render::map::all_variant_colors(),
+            keybindings: keybindings::load(&PathBuf::from(keybindings::KEYBINDINGS_FILE)),
+            speed: Speed::Paused,
+            show_stats: false,
+            hud: hud::new(),
+            icons: HashMap::new(),
+            error: None,
         };
-        (a, Command::none())
+        minimap::refresh(&mut a.minimap, &a.game_state, &a.colors);
+        a.hud = hud::refresh(&mut a.game_state);
+        let icons = Command::perform(async { render::resource_icons() }, Message::IconsDone);
+        (a, icons)
     }
 
     fn title(&self) -> String {
@@ -204,72 +405,185 @@ impl Application for AppState {
     fn update(&mut self, message: Message) -> Command<Message> {
         match message {
             Message::Build(t, pos) => {
-                self.game_state = building::build(t, pos, self.game_state.clone())
+                let res = if self.view_state.blueprint_mode && !menu::is_transition(t) {
+                    Ok(build_queue::place(t, pos, self.game_state.clone()))
+                } else {
+                    building::build(t, pos, self.game_state.clone())
+                };
+                match res {
+                    Ok(g) => self.set_game_state(g),
+                    Err(e) => self.error = Some(e),
+                }
+                minimap::mark_dirty(&mut self.minimap, pos);
             }
             Message::EndTurn => {
-                self.game_state = actionmachine::run(self.game_state.clone());
+                // anything a turn changes is, or was, driven by the action machine
+                for p in self.game_state.action_machine.iter().flatten() {
+                    minimap::mark_dirty(&mut self.minimap, *p);
+                }
+                match actionmachine::run(self.game_state.clone()) {
+                    Ok(g) => {
+                        self.set_game_state(g);
+                        if validate::enabled() {
+                            validate::report(&self.game_state);
+                        }
+                    }
+                    Err(e) => {
+                        // no point in failing the same turn again every tick
+                        self.speed = Speed::Paused;
+                        self.error = Some(e);
+                    }
+                }
+                for p in self.game_state.action_machine.iter().flatten() {
+                    minimap::mark_dirty(&mut self.minimap, *p);
+                }
+            }
+            Message::SetSpeed(speed) => {
+                self.speed = speed;
+            }
+            Message::DismissError => {
+                self.error = None;
+            }
+            Message::ToggleBlueprintMode => {
+                self.view_state.blueprint_mode = !self.view_state.blueprint_mode;
+            }
+            Message::ToggleRoadTool => {
+                let vs = &mut self.view_state;
+                vs.road_tool = !vs.road_tool;
+                vs.road_preview = None;
+            }
+            Message::RoadPreview(from, to) => {
+                self.view_state.road_preview = Some((from, to));
+            }
+            Message::ConfirmRoad => {
+                if let Some((from, to)) = self.view_state.road_preview {
+                    self.set_game_state(road_tool::confirm(from, to, self.game_state.clone()));
+                    for p in hexgrid::line(from, to) {
+                        minimap::mark_dirty(&mut self.minimap, p);
+                    }
+                }
+                self.view_state.road_preview = None;
+            }
+            Message::CancelRoad => {
+                self.view_state.road_preview = None;
+            }
+            Message::MoveBlueprint(index, is_up) => {
+                let g = if is_up {
+                    build_queue::move_up(index, self.game_state.clone())
+                } else {
+                    build_queue::move_down(index, self.game_state.clone())
+                };
+                self.set_game_state(g);
+            }
+            Message::RemoveBlueprint(index) => {
+                self.set_game_state(build_queue::remove(index, self.game_state.clone()));
+            }
+            Message::ShowStats(show) => {
+                self.show_stats = show;
+            }
+            Message::ExportStats => {
+                let path = PathBuf::from(STATS_FILE);
+                match stats::write_csv(&self.game_state.stats, &path) {
+                    Ok(_) => tracing::info!("wrote {:?}", path),
+                    Err(e) => tracing::error!("Error writing {:?}: {:?}", path, e),
+                }
+            }
+            Message::MinimapJump(p) => {
+                let vs = &mut self.view_state;
+                let (x, y) = hexgrid::to_pixel(p, vs.hex_size);
+                vs.top_left_pos = iced::Point {
+                    x: x - vs.width_px as f32 / 2.0,
+                    y: y - vs.height_px as f32 / 2.0,
+                };
+                self.view_changed()
+            }
+            Message::Select(p) => {
+                self.view_state.selected = Some(p);
+            }
+            Message::ScrollLog(older) => {
+                events::scroll(older, &mut self.view_state, &self.game_state);
+            }
+            Message::Locate(p) => {
+                self.view_state.selected = Some(p);
+                return self.update(Message::MinimapJump(p));
             }
             Message::NativeEvent(iced::Event::Mouse(iced::mouse::Event::CursorMoved {
                 position,
             })) => {
-                if self.game_state.io_cache.is_mousedown == true {
-                    let old_p = self.game_state.io_cache.latest_cursor;
+                if self.view_state.is_mousedown == true {
+                    let old_p = self.view_state.latest_cursor;
                     let delta = old_p - position;
-                    self.game_state.io_cache.top_left_pos =
-                        self.game_state.io_cache.top_left_pos + delta;
-                    re_calc_cells_in_view(&mut self.game_state)
+                    self.view_state.top_left_pos = self.view_state.top_left_pos + delta;
+                    self.view_changed()
+                }
+                self.view_state.latest_cursor = position;
+            }
+            Message::Pan(delta) => {
+                self.view_state.top_left_pos = self.view_state.top_left_pos + delta;
+                self.view_changed()
+            }
+            Message::NativeEvent(iced::Event::Keyboard(iced::keyboard::Event::KeyPressed {
+                key_code,
+                ..
+            })) => {
+                if let Some(m) = keybindings::to_message(
+                    &self.keybindings,
+                    key_code,
+                    &self.game_state,
+                    &self.view_state,
+                ) {
+                    return self.update(m);
                 }
-                self.game_state.io_cache.latest_cursor = position;
             }
             Message::Zoom(is_out) => {
                 if is_out {
-                    self.game_state.io_cache.cell_x_size =
-                        self.game_state.io_cache.cell_x_size / visualize_cell::ZOOM_FACTOR;
-                    self.game_state.io_cache.cell_y_size =
-                        self.game_state.io_cache.cell_y_size / visualize_cell::ZOOM_FACTOR;
+                    zoom(&mut self.view_state, 1.0 / visualize_cell::ZOOM_FACTOR);
                 } else {
-                    self.game_state.io_cache.cell_x_size =
-                        self.game_state.io_cache.cell_x_size * visualize_cell::ZOOM_FACTOR;
-                    self.game_state.io_cache.cell_y_size =
-                        self.game_state.io_cache.cell_y_size * visualize_cell::ZOOM_FACTOR;
+                    zoom(&mut self.view_state, visualize_cell::ZOOM_FACTOR);
                 }
-                re_calc_cells_in_view(&mut self.game_state)
+                self.view_changed()
             }
             Message::NativeEvent(iced::Event::Mouse(iced::mouse::Event::ButtonPressed(
                 iced::mouse::Button::Left,
             ))) => {
-                self.game_state.io_cache.is_mousedown = true;
+                self.view_state.is_mousedown = true;
             }
             Message::NativeEvent(iced::Event::Mouse(iced::mouse::Event::ButtonReleased(
                 iced::mouse::Button::Left,
             ))) => {
-                self.game_state.io_cache.is_mousedown = false;
+                self.view_state.is_mousedown = false;
             }
             Message::NativeEvent(iced::Event::Mouse(iced::mouse::Event::CursorLeft)) => {
-                self.game_state.io_cache.is_mousedown = false;
+                self.view_state.is_mousedown = false;
             }
             Message::NativeEvent(iced::Event::Mouse(iced::mouse::Event::WheelScrolled {
                 delta: iced_native::mouse::ScrollDelta::Lines { y, .. },
             })) => {
                 let d = y.abs() * visualize_cell::ZOOM_FACTOR;
                 if y < 0.0 {
-                    self.game_state.io_cache.cell_x_size = self.game_state.io_cache.cell_x_size / d;
-                    self.game_state.io_cache.cell_y_size = self.game_state.io_cache.cell_y_size / d;
+                    zoom(&mut self.view_state, 1.0 / d);
                 } else {
-                    self.game_state.io_cache.cell_x_size = self.game_state.io_cache.cell_x_size * d;
-                    self.game_state.io_cache.cell_y_size = self.game_state.io_cache.cell_y_size * d;
+                    zoom(&mut self.view_state, d);
                 }
-                re_calc_cells_in_view(&mut self.game_state)
+                self.view_changed()
             }
             Message::NativeEvent(iced::Event::Window(iced::window::Event::Resized {
                 width,
                 height,
             })) => {
-                self.game_state.io_cache.width_px = width as i32;
-                self.game_state.io_cache.height_px = height as i32;
-                re_calc_cells_in_view(&mut self.game_state)
+                self.view_state.width_px = width as i32;
+                self.view_state.height_px = height as i32;
+                self.view_changed()
             }
             Message::NativeEvent(_) => {}
+            Message::IconsDone(paths) => {
+                for (t, path) in paths {
+                    if let Ok(data) = fs::read(&path) {
+                        let handle = iced_native::image::Handle::from_memory(data);
+                        self.icons.insert(t, handle);
+                    }
+                }
+            }
             Message::ImgDone(i) => {
                 if i.path.exists() && i.path.is_file() {
                     let data = fs::read(i.path).unwrap();
@@ -278,64 +592,46 @@ impl Application for AppState {
                     // if there was a way to re-use the in-memory images for multiple
                     // renders that might speed things up
                     let handle = iced_native::image::Handle::from_memory(data);
-                    self.game_state.img_buffer.insert(i.data, handle);
+                    self.view_state.img_buffer.insert(i.data, handle);
                 } else {
                     unreachable!("{:?}", i);
                 }
             }
         }
+        minimap::refresh(&mut self.minimap, &self.game_state, &self.colors);
         Command::none()
     }
 
     fn view(&self) -> Element<Message> {
-        let start = std::time::Instant::now();
-        let view_matrix = hexgrid::view_port(
-            &self.game_state.matrix,
-            self.game_state.io_cache.top_left_hex,
-            self.game_state.io_cache.view_cells_x,
-            self.game_state.io_cache.view_cells_y,
+        if self.show_stats {
+            return self.stats_view();
+        }
+        let _span = tracing::trace_span!(
+            "view",
+            cells = self.view_state.view_cells_x * self.view_state.view_cells_y
+        )
+        .entered();
+        let board = hexview::view(
+            &self.game_state,
+            &self.view_state,
+            &self.colors,
+            &self.queues.send_img_job,
+        );
+        let inspector = inspector::view(
+            &self.game_state,
+            &self.view_state,
+            &self.queues.send_img_job,
         );
-        let _matrix_build = start.elapsed().as_millis();
-        let x = view_matrix
-            .map(|(x_index, i)| {
-                let padding: Element<'static, Message> =
-                    crate::Element::from(if (x_index as i32) % 2 == 0 {
-                        container("")
-                            .width(self.game_state.io_cache.cell_y_size)
-                            .height(self.game_state.io_cache.cell_x_size / 2.0)
-                    } else {
-                        container("").width(0).height(0)
-                    });
-                let mut data: Vec<Element<'static, Message>> = i
-                    .map(
-                        |(
-                            hexgrid::XYCont {
-                                x: x_index,
-                                y: y_index,
-                            },
-                            i,
-                        )| {
-                            visualize_cell::to_gui(
-                                hexgrid::XYCont {
-                                    x: x_index,
-                                    y: y_index,
-                                },
-                                i.clone(),
-                                &self.game_state,
-                                &self.queues.send_img_job,
-                            )
-                        },
-                    )
-                    .collect();
-                data.insert(0, padding);
-                crate::Element::from(iced::widget::Column::with_children(data))
-            })
-            .collect();
-        let _transform = start.elapsed().as_millis();
-        let matrix = crate::Element::from(iced::widget::Row::with_children(x));
-        let resources = crate::Element::from(visualize_cell::to_text(
-            format!("{:?}", self.game_state.resources).to_string(),
-        ));
+        let mut side_panel = vec![inspector];
+        if self.view_state.road_tool {
+            side_panel.push(road_tool::view(&self.game_state, &self.view_state));
+        }
+        side_panel.push(build_queue::view(&self.game_state));
+        side_panel.push(events::view(&self.game_state, &self.view_state));
+        let side_panel =
+            iced::widget::Column::with_children(side_panel).width(inspector::INSPECTOR_WIDTH);
+        let matrix = crate::Element::from(row![board, side_panel]);
+        let resources = hud::view(&self.hud, &self.game_state, &self.icons);
         let end_turn_content = visualize_cell::to_text("End Turn".to_string());
         let zoom_out_content = visualize_cell::to_text("Zoom Out".to_string());
         let zoom_in_content = visualize_cell::to_text("Zoom In".to_string());
@@ -344,52 +640,158 @@ impl Application for AppState {
             button(end_turn_content).on_press(Message::EndTurn),
             button(zoom_out_content).on_press(Message::Zoom(true)),
             button(zoom_in_content).on_press(Message::Zoom(false)),
+            button(visualize_cell::to_text("Stats".to_string())).on_press(Message::ShowStats(true)),
+            button(visualize_cell::to_text(
+                if self.view_state.blueprint_mode {
+                    "Blueprints: on"
+                } else {
+                    "Blueprints: off"
+                }
+                .to_string()
+            ))
+            .on_press(Message::ToggleBlueprintMode),
+            button(visualize_cell::to_text(
+                if self.view_state.road_tool {
+                    "Road tool: on"
+                } else {
+                    "Road tool: off"
+                }
+                .to_string()
+            ))
+            .on_press(Message::ToggleRoadTool),
+            minimap::view(&self.minimap, &self.view_state),
         ]);
+        let mut speed_buttons: Vec<Element<Message>> = vec![visualize_cell::to_text(format!(
+            "Turn {} ({})",
+            self.game_state.turn, self.speed
+        ))];
+        for speed in SPEEDS {
+            let b = button(visualize_cell::to_text(speed.to_string()));
+            speed_buttons.push(crate::Element::from(if speed == self.speed {
+                b
+            } else {
+                b.on_press(Message::SetSpeed(speed))
+            }));
+        }
+        let speed_controls = crate::Element::from(iced::widget::Row::with_children(speed_buttons));
         let ui_misc = crate::Element::from(row![visualize_cell::to_text(
             format!(
                 "{:?}",
-                self.game_state.io_cache.view_cells_x * self.game_state.io_cache.view_cells_y
+                self.view_state.view_cells_x * self.view_state.view_cells_y
             )
             .to_string()
         ),]);
-        let content =
-            iced::widget::Column::with_children(vec![matrix, resources, buttom_buttons, ui_misc]);
+        let mut rows = vec![matrix, resources, speed_controls, buttom_buttons, ui_misc];
+        if let Some(e) = self.error {
+            rows.push(crate::Element::from(row![
+                visualize_cell::to_text(e.to_string()),
+                button(visualize_cell::to_text("Dismiss".to_string()))
+                    .on_press(Message::DismissError),
+            ]));
+        }
+        let content = iced::widget::Column::with_children(rows);
 
-        let ret = container(content)
+        container(content)
             .width(Length::Fill)
             .height(Length::Fill)
             .padding(20)
-            .into();
-        let _total = start.elapsed().as_millis();
-        /*
-        println!(
-            "size:{} transform:{} matrix_build:{} other:{} total:{}",
-            self.game_state.io_cache.view_cells_x,
-            _matrix_build,
-            _transform - _matrix_build,
-            _total - _transform,
-            _total
-        );
-        // */
-        ret
+            .into()
     }
 }
 
-fn re_calc_cells_in_view(g: &mut GameState) {
-    (*g).io_cache.top_left_hex = approx((*g).io_cache.top_left_pos, g);
-    (*g).io_cache.view_cells_x = (*g).io_cache.width_px as i32 / (*g).io_cache.cell_x_size as i32;
-    (*g).io_cache.view_cells_y = (*g).io_cache.height_px as i32 / (*g).io_cache.cell_y_size as i32;
-    hexgrid::touch_all_chunks(
-        &mut g.matrix,
-        g.io_cache.top_left_hex,
-        g.io_cache.view_cells_x - 1,
-        g.io_cache.view_cells_y - 1,
-    );
+const STATS_FILE: &str = "stats.csv";
+
+impl AppState {
+    // anything shown that is derived from the game state is refreshed along with it
+    fn set_game_state(&mut self, g: GameState) {
+        self.game_state = g;
+        self.hud = hud::refresh(&mut self.game_state);
+    }
+
+    // the cells in view are read without loading, so the sim generates any it hasn't yet
+    fn view_changed(&mut self) {
+        re_calc_cells_in_view(&mut self.view_state);
+        let vs = &self.view_state;
+        sim::ensure_loaded(
+            vs.top_left_hex,
+            vs.view_cells_x,
+            vs.view_cells_y,
+            &mut self.game_state,
+        );
+    }
+
+    fn stats_view(&self) -> Element<Message> {
+        let buttons = crate::Element::from(row![
+            button(visualize_cell::to_text("Back".to_string())).on_press(Message::ShowStats(false)),
+            button(visualize_cell::to_text(format!("Export to {}", STATS_FILE)))
+                .on_press(Message::ExportStats),
+        ]);
+        let content = iced::widget::Column::with_children(vec![
+            buttons,
+            charts::view(&self.game_state.stats),
+        ]);
+        container(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .padding(20)
+            .into()
+    }
 }
 
-fn approx(iced::Point { x, y }: iced_native::Point, g: &GameState) -> hexgrid::XYCont<i32> {
-    hexgrid::XYCont {
-        x: (x / g.io_cache.cell_x_size) as i32,
-        y: (y / g.io_cache.cell_y_size) as i32,
+// the camera starts centered on the starting hub
+fn new_view_state() -> ViewState {
+    let hexgrid::XYCont {
+        x: start_x,
+        y: start_y,
+    } = sim::START_POS;
+    let start_view_cells_x = 7;
+    let start_view_cells_y = 5;
+    let hex_size = visualize_cell::START_HEX_SIZE;
+    let width_px = 1000;
+    let height_px = 1000;
+    ViewState {
+        top_left_pos: iced::Point {
+            x: (start_x as f32 - (start_view_cells_x / 2) as f32) * hex_size * 1.5,
+            y: (start_y as f32 - (start_view_cells_y / 2) as f32) * hex_size * 3.0_f32.sqrt(),
+        },
+        latest_cursor: iced::Point { x: 0.0, y: 0.0 },
+        is_mousedown: false,
+        top_left_hex: hexgrid::XYCont {
+            x: start_x as i32 - (start_view_cells_x / 2),
+            y: start_y as i32 - (start_view_cells_y / 2),
+        },
+        view_cells_x: start_view_cells_x,
+        view_cells_y: start_view_cells_y,
+        hex_size,
+        selected: None,
+        blueprint_mode: false,
+        road_tool: false,
+        road_preview: None,
+        log_offset: 0,
+        width_px: width_px,
+        height_px: height_px,
+        img_buffer: visualize_cell::new_img_buffer(),
     }
 }
+
+fn zoom(vs: &mut ViewState, factor: f32) {
+    // keep the center of the screen in place
+    let center = iced::Vector::new(vs.width_px as f32 / 2.0, vs.height_px as f32 / 2.0);
+    let world_center = vs.top_left_pos + center;
+    vs.hex_size = vs.hex_size * factor;
+    vs.top_left_pos = iced::Point {
+        x: world_center.x * factor,
+        y: world_center.y * factor,
+    } - center;
+}
+
+// the hexes cut by the screen edges are included, so there are no gaps while scrolling
+fn re_calc_cells_in_view(vs: &mut ViewState) {
+    vs.top_left_hex = approx(vs.top_left_pos, vs.hex_size) + hexgrid::XYCont { x: -1, y: -1 };
+    vs.view_cells_x = (vs.width_px as f32 / (vs.hex_size * 1.5)) as i32 + 3;
+    vs.view_cells_y = (vs.height_px as f32 / (vs.hex_size * 3.0_f32.sqrt())) as i32 + 3;
+}
+
+fn approx(iced::Point { x, y }: iced_native::Point, hex_size: f32) -> hexgrid::XYCont<i32> {
+    hexgrid::from_pixel((x, y), hex_size)
+}
@@ -0,0 +1,225 @@
+use std::collections::{HashMap, HashSet};
+
+use iced::widget::image;
+use iced_native::{
+    event, layout, mouse, renderer, widget::Tree, Clipboard, Color, Event, Layout, Length, Point,
+    Rectangle, Shell, Size, Widget,
+};
+
+use crate::{
+    hexgrid::{self, Pos, XYCont, CHUNK_SIZE},
+    widget, GameState, Message, ViewState,
+};
+use widget::Element;
+
+pub(crate) const MINIMAP_SIZE: f32 = 200.0;
+const VIEWPORT_COLOR: Color = Color {
+    r: 1.0,
+    g: 0.2,
+    b: 0.2,
+    a: 1.0,
+};
+
+// One pixel per cell of every loaded chunk.
+// Every chunk is its own image, so a changed chunk is drawn and uploaded again on its own,
+// the rest of the map stays as it was
+pub(crate) struct MinimapCache {
+    tiles: HashMap<Pos, image::Handle>,
+    dirty: HashSet<Pos>,
+    top_left: Pos,
+    size: Size<u32>,
+}
+
+pub(crate) fn new() -> MinimapCache {
+    MinimapCache {
+        tiles: HashMap::new(),
+        dirty: HashSet::new(),
+        top_left: XYCont { x: 0, y: 0 },
+        size: Size::new(0, 0),
+    }
+}
+
+// a change at p can also change its neighbours, which may sit in the next chunk over
+pub(crate) fn mark_dirty(cache: &mut MinimapCache, p: Pos) {
+    for q in std::iter::once(p).chain(hexgrid::neighbor_pos(p)) {
+        let (chunk, _) = hexgrid::to_chunk_keys(q);
+        cache.dirty.insert(chunk);
+    }
+}
+
+pub(crate) fn refresh(cache: &mut MinimapCache, g: &GameState, colors: &render::map::ColorCache) {
+    let mut added = false;
+    for chunk in hexgrid::loaded_chunks(&g.matrix) {
+        let is_new = !cache.tiles.contains_key(&chunk);
+        if is_new || cache.dirty.contains(&chunk) {
+            cache.tiles.insert(chunk, draw_tile(chunk, g, colors));
+            added = added || is_new;
+        }
+    }
+    cache.dirty.clear();
+    if added {
+        fit_bounds(cache);
+    }
+}
+
+fn draw_tile(chunk: Pos, g: &GameState, colors: &render::map::ColorCache) -> image::Handle {
+    let mut pixels = Vec::with_capacity(CHUNK_SIZE * CHUNK_SIZE * 4);
+    for dy in 0..CHUNK_SIZE as i32 {
+        for dx in 0..CHUNK_SIZE as i32 {
+            let cell = hexgrid::unsafe_get(chunk + XYCont { x: dx, y: dy }, &g.matrix);
+            let (r, g, b) = render::map::cell_color(cell, colors);
+            let channel = |v: f64| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+            pixels.extend_from_slice(&[channel(r), channel(g), channel(b), 255]);
+        }
+    }
+    image::Handle::from_pixels(CHUNK_SIZE as u32, CHUNK_SIZE as u32, pixels)
+}
+
+// the area the tiles cover, in cells
+fn fit_bounds(cache: &mut MinimapCache) {
+    let chunk = CHUNK_SIZE as i32;
+    let (Some(min_x), Some(max_x), Some(min_y), Some(max_y)) = (
+        cache.tiles.keys().map(|p| p.x).min(),
+        cache.tiles.keys().map(|p| p.x).max(),
+        cache.tiles.keys().map(|p| p.y).min(),
+        cache.tiles.keys().map(|p| p.y).max(),
+    ) else {
+        return;
+    };
+    cache.top_left = XYCont { x: min_x, y: min_y };
+    cache.size = Size::new(
+        (max_x + chunk - min_x) as u32,
+        (max_y + chunk - min_y) as u32,
+    );
+}
+
+pub(crate) fn view<'a>(cache: &'a MinimapCache, vs: &ViewState) -> Element<'a, Message> {
+    crate::Element::from(Minimap {
+        tiles: &cache.tiles,
+        top_left: cache.top_left,
+        size: cache.size,
+        view_top_left: vs.top_left_hex,
+        view_cells: Size::new(vs.view_cells_x as f32, vs.view_cells_y as f32),
+    })
+}
+
+struct Minimap<'a> {
+    tiles: &'a HashMap<Pos, image::Handle>,
+    top_left: Pos,
+    size: Size<u32>,
+    view_top_left: Pos,
+    view_cells: Size<f32>,
+}
+
+impl Minimap<'_> {
+    // pixels per cell and where the image starts, the image is centered and keeps its aspect
+    fn fit(&self, bounds: Rectangle) -> Option<(f32, Point)> {
+        if self.size.width == 0 || self.size.height == 0 {
+            return None;
+        }
+        let scale = f32::min(
+            bounds.width / self.size.width as f32,
+            bounds.height / self.size.height as f32,
+        );
+        let origin = Point::new(
+            bounds.x + (bounds.width - self.size.width as f32 * scale) / 2.0,
+            bounds.y + (bounds.height - self.size.height as f32 * scale) / 2.0,
+        );
+        Some((scale, origin))
+    }
+}
+
+impl<Renderer> Widget<Message, Renderer> for Minimap<'_>
+where
+    Renderer: iced_native::image::Renderer<Handle = image::Handle>,
+{
+    fn width(&self) -> Length {
+        Length::Fixed(MINIMAP_SIZE)
+    }
+
+    fn height(&self) -> Length {
+        Length::Fixed(MINIMAP_SIZE)
+    }
+
+    fn layout(&self, _renderer: &Renderer, _limits: &layout::Limits) -> layout::Node {
+        layout::Node::new(Size::new(MINIMAP_SIZE, MINIMAP_SIZE))
+    }
+
+    fn draw(
+        &self,
+        _state: &Tree,
+        renderer: &mut Renderer,
+        _theme: &Renderer::Theme,
+        _style: &renderer::Style,
+        layout: Layout<'_>,
+        _cursor_position: Point,
+        _viewport: &Rectangle,
+    ) {
+        let bounds = layout.bounds();
+        let Some((scale, origin)) = self.fit(bounds) else {
+            return;
+        };
+        let tile_size = CHUNK_SIZE as f32 * scale;
+        for (chunk, handle) in self.tiles.iter() {
+            renderer.draw(
+                handle.clone(),
+                Rectangle {
+                    x: origin.x + (chunk.x - self.top_left.x) as f32 * scale,
+                    y: origin.y + (chunk.y - self.top_left.y) as f32 * scale,
+                    width: tile_size,
+                    height: tile_size,
+                },
+            );
+        }
+        let view = Rectangle {
+            x: origin.x + (self.view_top_left.x - self.top_left.x) as f32 * scale,
+            y: origin.y + (self.view_top_left.y - self.top_left.y) as f32 * scale,
+            width: f32::max(self.view_cells.width * scale, 2.0),
+            height: f32::max(self.view_cells.height * scale, 2.0),
+        };
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds: view,
+                border_radius: 0.0.into(),
+                border_width: 1.0,
+                border_color: VIEWPORT_COLOR,
+            },
+            Color::TRANSPARENT,
+        );
+    }
+
+    fn on_event(
+        &mut self,
+        _state: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        _renderer: &Renderer,
+        _clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) -> event::Status {
+        let bounds = layout.bounds();
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
+                if bounds.contains(cursor_position) =>
+            {
+                if let Some((scale, origin)) = self.fit(bounds) {
+                    let cell = self.top_left
+                        + XYCont {
+                            x: ((cursor_position.x - origin.x) / scale) as i32,
+                            y: ((cursor_position.y - origin.y) / scale) as i32,
+                        };
+                    shell.publish(Message::MinimapJump(cell));
+                }
+                event::Status::Captured
+            }
+            _ => event::Status::Ignored,
+        }
+    }
+}
+
+impl<'a> From<Minimap<'a>> for Element<'a, Message> {
+    fn from(minimap: Minimap<'a>) -> Self {
+        Element::new(minimap)
+    }
+}
@@ -0,0 +1,86 @@
+use iced::widget::{button, column, container, row, text};
+use iced_native::Length;
+
+use crate::{
+    build_queue, building,
+    celldata::CellStateVariant,
+    components, css,
+    hexgrid::{self, Pos},
+    menu, resource, widget, GameState, Message, ViewState,
+};
+use widget::Element;
+
+const TEXT_SIZE: u16 = 16;
+
+// cells on the line between from and to that can get a road blueprint,
+// anything already built on or not yet explored is skipped
+pub(crate) fn plan(from: Pos, to: Pos, g: &GameState) -> Vec<Pos> {
+    hexgrid::line(from, to)
+        .filter(|p| {
+            matches!(
+                hexgrid::unsafe_get(*p, &g.matrix).variant,
+                CellStateVariant::Unused | CellStateVariant::Infrastructure
+            )
+        })
+        .collect()
+}
+
+// Unused cells are moved to the infrastructure menu right away, like clicking through it would
+pub(crate) fn confirm(from: Pos, to: Pos, mut g: GameState) -> GameState {
+    for p in plan(from, to, &g) {
+        if hexgrid::unsafe_get(p, &g.matrix).variant == CellStateVariant::Unused {
+            if let Some(c) = menu::transition(CellStateVariant::Infrastructure, p, &mut g) {
+                components::set(p, c, &mut g);
+            }
+        }
+        g = build_queue::place(CellStateVariant::Road, p, g);
+    }
+    g
+}
+
+pub(crate) fn view<'a>(g: &GameState, vs: &ViewState) -> Element<'a, Message> {
+    let content = match vs.road_preview {
+        None => {
+            crate::Element::from(text("Road tool: drag from one cell to another").size(TEXT_SIZE))
+        }
+        Some((from, to)) => {
+            let cells = plan(from, to, g);
+            let n = cells.len() as i32;
+            let one = building::cost(CellStateVariant::Road);
+            let materials: Vec<String> = enum_iterator::all::<resource::ResourceType>()
+                .filter(|t| one.materials[*t as usize] != 0)
+                .map(|t| format!("{} {:?}", one.materials[t as usize] * n, t))
+                .collect();
+            let skipped = hexgrid::distance(from, to) + 1 - n;
+            let summary = format!(
+                "{} road(s), {} builder turns{}{}",
+                n,
+                one.builder_turns * n,
+                if materials.is_empty() {
+                    "".to_string()
+                } else {
+                    format!(", {}", materials.join(", "))
+                },
+                if skipped > 0 {
+                    format!(", {} cell(s) skipped", skipped)
+                } else {
+                    "".to_string()
+                }
+            );
+            crate::Element::from(column![
+                text(summary).size(TEXT_SIZE),
+                row![
+                    button(text("Place blueprints").size(TEXT_SIZE)).on_press(Message::ConfirmRoad),
+                    button(text("Cancel").size(TEXT_SIZE)).on_press(Message::CancelRoad),
+                ]
+                .spacing(4),
+            ])
+        }
+    };
+    crate::Element::from(
+        container(content)
+            .width(Length::Fill)
+            .padding(4)
+            .style(css::Container::Bordered),
+    )
+}
@@ -1,9 +1,7 @@
-use std::collections::HashMap;
-
 use crate::{
     celldata::{self},
     css::{self},
-    hexgrid, menu, widget, GameState, Message,
+    hexgrid, menu, widget, GameState, Message, ViewState,
 };
 use iced::{
     alignment::{Horizontal, Vertical},
@@ -12,30 +10,30 @@ use iced::{
 use iced_native::Length;
 use widget::Element;
 
-pub(crate) type ImgBuffer = HashMap<celldata::CellState, image::Handle>;
+pub(crate) type ImgBuffer = im::HashMap<celldata::CellState, image::Handle>;
 
 pub(crate) fn new_img_buffer() -> ImgBuffer {
-    return HashMap::new();
+    return im::HashMap::new();
 }
 
 fn has_image(s: celldata::CellState, buff: &ImgBuffer) -> Option<&image::Handle> {
     buff.get(&s)
 }
 
-pub(crate) const START_CELL_X_SIZE: f32 = 100.0;
-pub(crate) const START_CELL_Y_SIZE: f32 = 125.0;
+pub(crate) use render::{START_CELL_X_SIZE, START_CELL_Y_SIZE};
+pub(crate) const START_HEX_SIZE: f32 = 60.0;
 pub(crate) const ZOOM_FACTOR: f32 = 1.5;
 
 pub(crate) fn to_gui<'a>(
     pos: hexgrid::XYCont<i32>,
     s: celldata::CellState,
     g: &GameState,
+    vs: &ViewState,
     send: &std::sync::mpsc::Sender<celldata::CellState>,
 ) -> Element<'a, Message> {
-    let imgs: &ImgBuffer = &g.img_buffer;
-    let content = match menu::has_actions(pos, s, g) {
-        Some(actions) => render_action_cell(actions, pos, imgs, s, send),
-        None => match has_image(s, imgs) {
+    let content = match menu::available_actions(pos, s, g, vs) {
+        Some(actions) => render_action_cell(actions, pos, &vs.img_buffer, s, send),
+        None => match has_image(s, &vs.img_buffer) {
             Some(img_handle) => to_image(img_handle),
             None => {
                 let _ = send.send(s);
@@ -46,8 +44,8 @@ pub(crate) fn to_gui<'a>(
 
     crate::Element::from(
         container(content)
-            .width(g.io_cache.cell_y_size)
-            .height(g.io_cache.cell_x_size)
+            .width(START_CELL_Y_SIZE)
+            .height(START_CELL_X_SIZE)
             .style(css::Container::Bordered)
             .align_x(Horizontal::Center)
             .align_y(Vertical::Center),
@@ -61,18 +59,20 @@ fn render_action_cell<'a>(
     s: celldata::CellState,
     send: &std::sync::mpsc::Sender<celldata::CellState>,
 ) -> Element<'a, Message> {
-    let layout = if actions.len() > 2 {
-        to_rectangle(actions, 4, 2)
+    let numbered = actions.into_iter().enumerate();
+    let layout = if numbered.len() > 2 {
+        to_rectangle(numbered, 4, 2)
     } else {
-        to_rectangle(actions, 2, 1)
+        to_rectangle(numbered, 2, 1)
     };
     let mut grid: Vec<_> = layout
         .iter()
         .map(|v| {
             crate::Element::from(iced::widget::row(
                 v.into_iter()
-                    .map(|i| {
-                        let button_content = to_text(i.to_string());
+                    .map(|(n, i)| {
+                        // the number is the hotkey, see keybindings::Action::Hotkey
+                        let button_content = to_text(format!("{} {}", n + 1, i));
                         crate::Element::from(
                             button(button_content).on_press(Message::Build(*i, pos)),
                         )
@@ -92,12 +92,9 @@ fn render_action_cell<'a>(
     crate::Element::from(iced::widget::column(grid))
 }
 
+// shown until the image is generated, the details are in the inspector
 fn backup_formatter<'a>(s: celldata::CellState) -> Element<'a, Message> {
-    //dbg!(s);
-    match s.data {
-        celldata::CellStateData::Unit => to_text(format!("DEFAULT:{:?}", s.variant).to_string()),
-        _ => to_text(format!("DEFAULT:{:?}", s).to_string()),
-    }
+    to_text(s.variant.to_string())
 }
 
 // example, w=2, h=3, s=[1,2,3,4,5]